@@ -0,0 +1,125 @@
+//! Airtime for every transmission. Periodic slots are jittered so boards powered on together
+//! drift apart, and all frames share budget so senders cannot keep radio and its interrupt busy.
+
+#![deny(clippy::indexing_slicing, clippy::unwrap_used)]
+
+use crate::tick;
+
+/// Frames sent back to back at most, longest message fits.
+pub const BURST: u32 = 8;
+/// RTC0 ticks earning one frame, 2 per second sustained.
+const REFILL_TICKS: u32 = tick::PER_SEC / 2;
+/// Slot waits up to period / `JITTER` longer.
+const JITTER: u32 = 4;
+
+pub struct Airtime {
+    tokens: u32,
+    at: u32,
+    /// xorshift state, never 0.
+    state: u32,
+}
+
+impl Airtime {
+    /// `seed` should differ among boards, device id does.
+    pub const fn new(seed: u32, now: u32) -> Self {
+        Airtime {
+            tokens: BURST,
+            at: now,
+            state: seed | 1,
+        }
+    }
+
+    /// Spends `frames` of budget, `false` spending nothing when there is not enough.
+    pub fn take(&mut self, now: u32, frames: u32) -> bool {
+        // ticks short of whole frame carry over, full bucket earns nothing
+        let earned = tick::elapsed(self.at, now) / REFILL_TICKS;
+        self.tokens = self.tokens.saturating_add(earned).min(BURST);
+        if self.tokens == BURST {
+            self.at = now;
+        } else {
+            self.at = self.at.wrapping_add(earned * REFILL_TICKS);
+        }
+
+        if self.tokens < frames {
+            return false;
+        }
+        self.tokens -= frames;
+        true
+    }
+
+    fn jitter(&mut self, max: u32) -> u32 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        self.state % max.max(1)
+    }
+}
+
+/// Periodic transmission.
+pub struct Slot {
+    period: u32,
+    last: u32,
+    wait: u32,
+}
+
+impl Slot {
+    pub const fn new(period: u32, now: u32) -> Self {
+        Slot {
+            period,
+            last: now,
+            wait: period,
+        }
+    }
+
+    /// `true` when slot came and budget allows sending, slot without budget is lost.
+    pub fn due(&mut self, now: u32, airtime: &mut Airtime) -> bool {
+        if tick::elapsed(self.last, now) < self.wait {
+            return false;
+        }
+
+        self.last = now;
+        self.wait = self.period + airtime.jitter(self.period / JITTER);
+        airtime.take(now, 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn burst() {
+        let mut air = Airtime::new(1, 0);
+        assert!(air.take(0, BURST));
+        assert!(!air.take(0, 1));
+    }
+
+    #[test]
+    fn refill_keeps_leftover_ticks() {
+        let mut air = Airtime::new(1, 0);
+        assert!(air.take(0, BURST));
+
+        // calls 30 ticks apart, frame earned every 50 still
+        let mut sent = 0;
+        for now in (30..=30 * 50).step_by(30) {
+            sent += air.take(now, 1) as u32;
+        }
+        assert_eq!(sent, 30 * 50 / REFILL_TICKS);
+    }
+
+    #[test]
+    fn full_bucket_earns_nothing() {
+        let mut air = Airtime::new(1, 0);
+        assert!(air.take(10 * REFILL_TICKS + 40, 1));
+        assert!(!air.take(10 * REFILL_TICKS + 40 + REFILL_TICKS - 1, BURST));
+        assert!(air.take(10 * REFILL_TICKS + 40 + REFILL_TICKS, BURST));
+    }
+
+    #[test]
+    fn counter_wraps() {
+        let start = 0xFF_FFF0;
+        let mut air = Airtime::new(1, start);
+        assert!(air.take(start, BURST));
+        assert!(air.take(start.wrapping_add(REFILL_TICKS) & 0xFF_FFFF, 1));
+    }
+}
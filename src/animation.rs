@@ -0,0 +1,166 @@
+//! Display effects driven frame by frame from RTC0.
+
+use crate::{
+    fader::Fade,
+    flap::Flap,
+    memory::Lattice,
+    message::Message,
+    scroller::{Marquee, Vertical},
+    typer::Typer,
+};
+use microbit::hal::Rng;
+
+pub trait Animation {
+    /// Starts `text` from its beginning.
+    fn set_text(&mut self, text: Message);
+    fn text(&self) -> &Message;
+    /// Pixels lit from now on.
+    fn set_sparkle(&mut self, sparkle: Sparkle);
+    /// Scrolling effects run opposite way, restarting text.
+    fn set_reversed(&mut self, _reversed: bool) {}
+    /// Blank display, text from its beginning.
+    fn reset(&mut self);
+    /// Char last frame finished, `None` mid char.
+    fn boundary(&self) -> Option<char> {
+        None
+    }
+    fn next_frame(&mut self, rng: &mut Rng) -> Lattice;
+}
+
+/// Brightness of lit pixel is random in `floor..=ceil`, lower draws clamp to `floor`.
+#[derive(Clone, Copy)]
+pub struct Sparkle {
+    pub floor: u8,
+    pub ceil: u8,
+}
+
+impl Sparkle {
+    pub const DEFAULT: Sparkle = Sparkle { floor: 5, ceil: 9 };
+    /// Every lit pixel at full brightness.
+    pub const STEADY: Sparkle = Sparkle { floor: 9, ceil: 9 };
+
+    pub fn brightness(&self, rng: &mut Rng) -> u8 {
+        (rng.random_u8() % (self.ceil + 1)).max(self.floor)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Marquee,
+    Fade,
+    Vertical,
+    Flap,
+    Typer,
+}
+
+impl Kind {
+    pub const ALL: [Kind; 5] = [
+        Kind::Marquee,
+        Kind::Fade,
+        Kind::Vertical,
+        Kind::Flap,
+        Kind::Typer,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Kind::Marquee => "marquee",
+            Kind::Fade => "fade",
+            Kind::Vertical => "vertical",
+            Kind::Flap => "flap",
+            Kind::Typer => "type",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Kind> {
+        Kind::ALL.into_iter().find(|k| k.name() == name)
+    }
+
+    pub fn next(self) -> Kind {
+        let ix = Kind::ALL.iter().position(|k| *k == self).unwrap_or(0);
+        Kind::ALL[(ix + 1) % Kind::ALL.len()]
+    }
+}
+
+/// Effect selected at runtime, only one lives in RAM.
+pub enum Effect {
+    Marquee(Marquee),
+    Fade(Fade),
+    Vertical(Vertical),
+    Flap(Flap),
+    Typer(Typer),
+}
+
+impl Effect {
+    pub const fn new(kind: Kind, text: Message, sparkle: Sparkle) -> Self {
+        match kind {
+            Kind::Marquee => Effect::Marquee(Marquee::new(text, sparkle)),
+            Kind::Fade => Effect::Fade(Fade::new(text, sparkle)),
+            Kind::Vertical => Effect::Vertical(Vertical::new(text, sparkle)),
+            Kind::Flap => Effect::Flap(Flap::new(text, sparkle)),
+            Kind::Typer => Effect::Typer(Typer::new(text, sparkle)),
+        }
+    }
+
+    pub fn kind(&self) -> Kind {
+        match self {
+            Effect::Marquee(_) => Kind::Marquee,
+            Effect::Fade(_) => Kind::Fade,
+            Effect::Vertical(_) => Kind::Vertical,
+            Effect::Flap(_) => Kind::Flap,
+            Effect::Typer(_) => Kind::Typer,
+        }
+    }
+
+    fn animation(&mut self) -> &mut dyn Animation {
+        match self {
+            Effect::Marquee(a) => a,
+            Effect::Fade(a) => a,
+            Effect::Vertical(a) => a,
+            Effect::Flap(a) => a,
+            Effect::Typer(a) => a,
+        }
+    }
+}
+
+impl Animation for Effect {
+    fn set_text(&mut self, text: Message) {
+        self.animation().set_text(text);
+    }
+
+    fn text(&self) -> &Message {
+        match self {
+            Effect::Marquee(a) => a.text(),
+            Effect::Fade(a) => a.text(),
+            Effect::Vertical(a) => a.text(),
+            Effect::Flap(a) => a.text(),
+            Effect::Typer(a) => a.text(),
+        }
+    }
+
+    fn set_sparkle(&mut self, sparkle: Sparkle) {
+        self.animation().set_sparkle(sparkle);
+    }
+
+    fn set_reversed(&mut self, reversed: bool) {
+        self.animation().set_reversed(reversed);
+    }
+
+    fn reset(&mut self) {
+        self.animation().reset();
+    }
+
+    fn boundary(&self) -> Option<char> {
+        match self {
+            Effect::Marquee(a) => a.boundary(),
+            Effect::Fade(a) => a.boundary(),
+            Effect::Vertical(a) => a.boundary(),
+            Effect::Flap(a) => a.boundary(),
+            Effect::Typer(a) => a.boundary(),
+        }
+    }
+
+    fn next_frame(&mut self, rng: &mut Rng) -> Lattice {
+        self.animation().next_frame(rng)
+    }
+}
@@ -0,0 +1,59 @@
+//! Check-in session recording boards whose presence beacons were heard.
+
+use crate::tick;
+
+/// RTC0 ticks between own beacons.
+pub const BEACON_TICKS: u32 = 5 * tick::PER_SEC;
+/// Boards one session remembers.
+const NODES: usize = 32;
+
+pub struct Attendance {
+    /// Node and RTC0 tick it was first heard at.
+    heard: [(u32, u32); NODES],
+    len: usize,
+    /// Session start tick.
+    since: u32,
+    recording: bool,
+}
+
+impl Attendance {
+    pub const fn new() -> Self {
+        Attendance {
+            heard: [(0, 0); NODES],
+            len: 0,
+            since: 0,
+            recording: false,
+        }
+    }
+
+    /// Forgets previous session.
+    pub fn start(&mut self, now: u32) {
+        self.len = 0;
+        self.since = now;
+        self.recording = true;
+    }
+
+    /// Keeps list of session ended.
+    pub fn stop(&mut self) {
+        self.recording = false;
+    }
+
+    /// Beacon of `node` heard at `now`, boards over `NODES` are not recorded.
+    pub fn heard(&mut self, node: u32, now: u32) {
+        if !self.recording || self.heard[..self.len].iter().any(|(n, _)| *n == node) {
+            return;
+        }
+
+        if self.len < NODES {
+            self.heard[self.len] = (node, now);
+            self.len += 1;
+        }
+    }
+
+    /// Node and seconds into session it was first heard.
+    pub fn list(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.heard[..self.len]
+            .iter()
+            .map(|(node, at)| (*node, tick::elapsed(self.since, *at) / tick::PER_SEC))
+    }
+}
@@ -0,0 +1,54 @@
+//! Attract mode cycling effects and stored messages until any button is pressed.
+
+use crate::{animation::Kind, config::Config, message::Store, tick};
+
+/// Ticks each effect is shown.
+const STEP_TICKS: u32 = 8 * tick::PER_SEC;
+
+pub struct Attract {
+    /// Effect and message to return to.
+    from: Option<(Kind, usize)>,
+    since: u32,
+}
+
+impl Attract {
+    pub const fn new() -> Self {
+        Attract {
+            from: None,
+            since: 0,
+        }
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.from.is_some()
+    }
+
+    /// Follows `cfg.attract`, `now` in RTC0 ticks. Message changes after all effects were shown,
+    /// to one shown least with `cfg.fair`.
+    pub fn poll(&mut self, cfg: &mut Config, store: &mut Store, now: u32) {
+        match self.from {
+            None if cfg.attract => {
+                self.from = Some((cfg.effect, cfg.active));
+                self.since = now;
+            }
+            Some((effect, active)) if !cfg.attract => {
+                cfg.effect = effect;
+                cfg.active = active;
+                self.from = None;
+            }
+            Some(_) if tick::elapsed(self.since, now) >= STEP_TICKS => {
+                self.since = now;
+
+                cfg.effect = cfg.effect.next();
+                if cfg.effect == Kind::ALL[0] {
+                    cfg.active = if cfg.fair {
+                        store.least_aired()
+                    } else {
+                        store.rotate(cfg.active, cfg.rotation)
+                    };
+                }
+            }
+            _ => {}
+        }
+    }
+}
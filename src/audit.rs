@@ -0,0 +1,115 @@
+//! Recent configuration changes with their source, listed by `/audit`.
+
+use crate::{animation::Kind, config::Config};
+
+/// Changes remembered, oldest is overwritten.
+const ENTRIES: usize = 16;
+
+#[derive(Clone, Copy)]
+pub enum Source {
+    Serial,
+    Button,
+    Gesture,
+    Soak,
+}
+
+impl Source {
+    pub fn name(self) -> &'static str {
+        match self {
+            Source::Serial => "serial",
+            Source::Button => "button",
+            Source::Gesture => "gesture",
+            Source::Soak => "soak",
+        }
+    }
+}
+
+/// Reads setting value, effect as `Kind::ALL` index, orientation as its bits.
+type Get = fn(&Config) -> u16;
+
+/// Audited settings.
+const FIELDS: [(&str, Get); 26] = [
+    ("speed", |c| c.speed as u16),
+    ("pause", |c| c.paused as u16),
+    ("effect", |c| {
+        Kind::ALL.iter().position(|k| *k == c.effect).unwrap_or(0) as u16
+    }),
+    ("attract", |c| c.attract as u16),
+    ("reversed", |c| c.reversed as u16),
+    ("idle", |c| c.idle_secs),
+    ("canvas", |c| c.canvas as u16),
+    ("badge", |c| c.badge as u16),
+    ("kiosk", |c| c.kiosk as u16),
+    ("access", |c| c.access as u16),
+    ("pulse", |c| c.pulse as u16),
+    ("heartbeat", |c| c.heartbeat as u16),
+    ("ants", |c| c.ants as u16),
+    ("trail", |c| c.trail as u16),
+    ("mood", |c| c.mood as u16),
+    ("stutter", |c| c.stutter as u16),
+    ("autospeed", |c| c.auto_speed as u16),
+    ("fair", |c| c.fair as u16),
+    ("censor", |c| c.censor as u16),
+    ("moderate", |c| c.moderate as u16),
+    ("orient", |c| c.orient.bits() as u16),
+    ("safe", |c| c.safe as u16),
+    ("wipe", |c| c.wipe as u16),
+    ("overflow", |c| c.overflow as u16),
+    ("playback", |c| c.playback as u16),
+    ("rotation", |c| c.rotation as u16),
+];
+
+#[derive(Clone, Copy)]
+pub struct Entry {
+    /// RTC0 tick.
+    pub at: u32,
+    pub source: Source,
+    pub field: &'static str,
+    pub value: u16,
+}
+
+pub struct Log {
+    entries: [Entry; ENTRIES],
+    len: usize,
+    next: usize,
+}
+
+impl Log {
+    pub const fn new() -> Self {
+        Log {
+            entries: [Entry {
+                at: 0,
+                source: Source::Serial,
+                field: "",
+                value: 0,
+            }; ENTRIES],
+            len: 0,
+            next: 0,
+        }
+    }
+
+    /// Records every audited setting differing between `old` and `new`.
+    pub fn record(&mut self, old: &Config, new: &Config, source: Source, now: u32) {
+        for (field, get) in FIELDS {
+            let value = get(new);
+            if value == get(old) {
+                continue;
+            }
+
+            self.entries[self.next] = Entry {
+                at: now,
+                source,
+                field,
+                value,
+            };
+            self.next = (self.next + 1) % ENTRIES;
+            self.len = (self.len + 1).min(ENTRIES);
+        }
+    }
+
+    /// Oldest first.
+    pub fn list(&self) -> impl Iterator<Item = &Entry> {
+        let start = (self.next + ENTRIES - self.len) % ENTRIES;
+        (0..self.len).map(move |ix| &self.entries[(start + ix) % ENTRIES])
+    }
+}
@@ -0,0 +1,90 @@
+//! Name badge mode, attention intro, text passes and rests with display off.
+
+#![deny(clippy::indexing_slicing, clippy::unwrap_used)]
+
+use crate::{memory::Lattice, tick};
+
+/// Ring radius per intro frame.
+const INTRO: [usize; 5] = [0, 1, 2, 1, 0];
+/// RTC0 ticks of text after intro.
+const SHOW_TICKS: u32 = 12 * tick::PER_SEC;
+/// RTC0 ticks with display off between passes.
+const REST_TICKS: u32 = 8 * tick::PER_SEC;
+
+pub enum Step {
+    Intro(Lattice),
+    /// `restart` starts text from its beginning.
+    Text {
+        restart: bool,
+    },
+    Rest,
+}
+
+#[derive(Clone, Copy)]
+enum Phase {
+    /// Ring radii left.
+    Intro(&'static [usize]),
+    Text,
+    Rest,
+}
+
+pub struct Badge {
+    phase: Phase,
+    since: u32,
+}
+
+impl Badge {
+    pub const fn new() -> Self {
+        Badge {
+            phase: Phase::Intro(&INTRO),
+            since: 0,
+        }
+    }
+
+    /// Starts over with intro.
+    pub fn reset(&mut self) {
+        self.phase = Phase::Intro(&INTRO);
+    }
+
+    /// Called each frame, `now` in RTC0 ticks.
+    pub fn step(&mut self, now: u32) -> Step {
+        match self.phase {
+            Phase::Intro([radius, rest @ ..]) => {
+                self.phase = Phase::Intro(rest);
+                Step::Intro(ring(*radius))
+            }
+            Phase::Intro([]) => {
+                self.phase = Phase::Text;
+                self.since = now;
+                Step::Text { restart: true }
+            }
+            Phase::Text if tick::elapsed(self.since, now) < SHOW_TICKS => {
+                Step::Text { restart: false }
+            }
+            Phase::Text => {
+                self.phase = Phase::Rest;
+                self.since = now;
+                Step::Rest
+            }
+            Phase::Rest if tick::elapsed(self.since, now) < REST_TICKS => Step::Rest,
+            Phase::Rest => {
+                self.reset();
+                self.step(now)
+            }
+        }
+    }
+}
+
+/// Square ring of `radius` around center.
+fn ring(radius: usize) -> Lattice {
+    let mut latt = [[0; 5]; 5];
+
+    for (rix, row) in latt.iter_mut().enumerate() {
+        for (cix, px) in row.iter_mut().enumerate() {
+            if rix.abs_diff(2).max(cix.abs_diff(2)) == radius {
+                *px = 9;
+            }
+        }
+    }
+    latt
+}
@@ -0,0 +1,317 @@
+//! Fixed point calculator and unit conversion for console.
+//!
+//! Numbers carry 3 decimals, `+ - * /` and parentheses follow usual precedence.
+
+#![deny(clippy::indexing_slicing, clippy::unwrap_used)]
+
+use core::fmt;
+
+const SCALE: i64 = 1000;
+/// Nested parentheses and unary minuses, bounds recursion.
+const DEPTH: u8 = 8;
+
+#[derive(Clone, Copy)]
+pub struct Fixed(i64);
+
+impl fmt::Display for Fixed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let abs = self.0.unsigned_abs();
+        let (int, mut frac) = (abs / SCALE as u64, abs % SCALE as u64);
+
+        write!(f, "{sign}{int}")?;
+        if frac == 0 {
+            return Ok(());
+        }
+
+        let mut digits = 3;
+        while frac % 10 == 0 {
+            frac /= 10;
+            digits -= 1;
+        }
+        write!(f, ".{frac:0digits$}")
+    }
+}
+
+/// `None` on syntax error, division by zero or overflow.
+pub fn eval(expr: &str) -> Option<Fixed> {
+    let mut parser = Parser {
+        bytes: expr.as_bytes(),
+        ix: 0,
+    };
+
+    let val = parser.expr(0)?;
+    parser.skip_spaces();
+    match parser.ix == parser.bytes.len() {
+        true => Some(Fixed(val)),
+        false => None,
+    }
+}
+
+/// `<value> <from> <to>`, units `c f k`, `mm cm m km in ft mi`, `g kg oz lb`.
+pub fn conv(args: &str) -> Option<Fixed> {
+    let mut parts = args.split_whitespace();
+    let val = eval(parts.next()?)?.0;
+    let from = unit(parts.next()?)?;
+    let to = unit(parts.next()?)?;
+
+    if parts.next().is_some() || from.dim != to.dim {
+        return None;
+    }
+
+    // single division keeps precision, i128 cannot overflow here
+    let (val, from_post, to_post) = (val as i128, from.post as i128, to.post as i128);
+    let num = (val + from.pre as i128) * from.num as i128 * to.den as i128
+        + (from_post - to_post) * from.den as i128 * to.den as i128;
+    let val = num / (from.den as i128 * to.num as i128) - to.pre as i128;
+
+    i64::try_from(val).ok().map(Fixed)
+}
+
+#[derive(PartialEq, Eq)]
+enum Dim {
+    Temperature,
+    Length,
+    Mass,
+}
+
+/// Base unit value is `(v + pre) * num / den + post`.
+struct Unit {
+    dim: Dim,
+    pre: i64,
+    num: i64,
+    den: i64,
+    post: i64,
+}
+
+fn unit(name: &str) -> Option<Unit> {
+    let linear = |dim, num, den| Unit {
+        dim,
+        pre: 0,
+        num,
+        den,
+        post: 0,
+    };
+
+    let unit = match name {
+        "k" => linear(Dim::Temperature, 1, 1),
+        "c" => Unit {
+            post: 273_150,
+            ..linear(Dim::Temperature, 1, 1)
+        },
+        "f" => Unit {
+            pre: -32 * SCALE,
+            post: 273_150,
+            ..linear(Dim::Temperature, 5, 9)
+        },
+        "mm" => linear(Dim::Length, 1, 1000),
+        "cm" => linear(Dim::Length, 1, 100),
+        "m" => linear(Dim::Length, 1, 1),
+        "km" => linear(Dim::Length, 1000, 1),
+        "in" => linear(Dim::Length, 254, 10_000),
+        "ft" => linear(Dim::Length, 3048, 10_000),
+        "mi" => linear(Dim::Length, 1_609_344, 1000),
+        "g" => linear(Dim::Mass, 1, 1000),
+        "kg" => linear(Dim::Mass, 1, 1),
+        "oz" => linear(Dim::Mass, 28_349_523, 1_000_000_000),
+        "lb" => linear(Dim::Mass, 45_359_237, 100_000_000),
+        _ => return None,
+    };
+    Some(unit)
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    ix: usize,
+}
+
+impl Parser<'_> {
+    fn expr(&mut self, depth: u8) -> Option<i64> {
+        let mut val = self.term(depth)?;
+
+        loop {
+            val = match self.peek() {
+                Some(b'+') => {
+                    self.ix += 1;
+                    val.checked_add(self.term(depth)?)?
+                }
+                Some(b'-') => {
+                    self.ix += 1;
+                    val.checked_sub(self.term(depth)?)?
+                }
+                _ => return Some(val),
+            };
+        }
+    }
+
+    fn term(&mut self, depth: u8) -> Option<i64> {
+        let mut val = self.factor(depth)?;
+
+        loop {
+            val = match self.peek() {
+                Some(b'*') => {
+                    self.ix += 1;
+                    val.checked_mul(self.factor(depth)?)? / SCALE
+                }
+                Some(b'/') => {
+                    self.ix += 1;
+                    val.checked_mul(SCALE)?.checked_div(self.factor(depth)?)?
+                }
+                _ => return Some(val),
+            };
+        }
+    }
+
+    fn factor(&mut self, depth: u8) -> Option<i64> {
+        match self.peek()? {
+            b'-' if depth < DEPTH => {
+                self.ix += 1;
+                self.factor(depth + 1)?.checked_neg()
+            }
+            b'(' if depth < DEPTH => {
+                self.ix += 1;
+                let val = self.expr(depth + 1)?;
+                (self.peek()? == b')').then(|| self.ix += 1)?;
+                Some(val)
+            }
+            b'0'..=b'9' | b'.' => self.number(),
+            _ => None,
+        }
+    }
+
+    fn number(&mut self) -> Option<i64> {
+        let mut int: i64 = 0;
+        let mut frac = None;
+        let mut digits = 0;
+
+        while let Some(&b) = self.bytes.get(self.ix) {
+            match (b, frac) {
+                (b'0'..=b'9', None) => {
+                    int = int.checked_mul(10)?.checked_add((b - b'0') as i64)?;
+                }
+                (b'0'..=b'9', Some(f)) => {
+                    // digits past 3rd are cut
+                    if digits < 3 {
+                        frac = Some(f * 10 + (b - b'0') as i64);
+                        digits += 1;
+                    }
+                }
+                (b'.', None) => frac = Some(0),
+                _ => break,
+            }
+            self.ix += 1;
+        }
+
+        let frac = frac.unwrap_or(0) * 10_i64.pow(3 - digits);
+        int.checked_mul(SCALE)?.checked_add(frac)
+    }
+
+    /// Next byte that is not space.
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_spaces();
+        self.bytes.get(self.ix).copied()
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.bytes.get(self.ix) == Some(&b' ') {
+            self.ix += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval_str(expr: &str) -> Option<String> {
+        eval(expr).map(|v| v.to_string())
+    }
+
+    fn conv_str(args: &str) -> Option<String> {
+        conv(args).map(|v| v.to_string())
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(eval_str("42").as_deref(), Some("42"));
+        assert_eq!(eval_str("1.5").as_deref(), Some("1.5"));
+        assert_eq!(eval_str(".25").as_deref(), Some("0.25"));
+        assert_eq!(eval_str("2.1239").as_deref(), Some("2.123"));
+        assert_eq!(eval_str(" 7 ").as_deref(), Some("7"));
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(eval_str("12*7+3").as_deref(), Some("87"));
+        assert_eq!(eval_str("3+12*7").as_deref(), Some("87"));
+        assert_eq!(eval_str("10-4-3").as_deref(), Some("3"));
+        assert_eq!(eval_str("8/4/2").as_deref(), Some("1"));
+        assert_eq!(eval_str("(3+4)*2").as_deref(), Some("14"));
+        assert_eq!(eval_str("2 * (1 + (2 - 4))").as_deref(), Some("-2"));
+    }
+
+    #[test]
+    fn fractions() {
+        assert_eq!(eval_str("1/3").as_deref(), Some("0.333"));
+        assert_eq!(eval_str("0.5*0.5").as_deref(), Some("0.25"));
+        assert_eq!(eval_str("-7/2").as_deref(), Some("-3.5"));
+    }
+
+    #[test]
+    fn unary_minus() {
+        assert_eq!(eval_str("-3").as_deref(), Some("-3"));
+        assert_eq!(eval_str("--3").as_deref(), Some("3"));
+        assert_eq!(eval_str("2*-3").as_deref(), Some("-6"));
+        assert_eq!(eval_str("-(1+2)").as_deref(), Some("-3"));
+    }
+
+    #[test]
+    fn errors() {
+        for expr in ["", "1+", "(1", "1)", "abc", "1 2", "2**3", "1/0", "1/(2-2)"] {
+            assert!(eval(expr).is_none(), "{expr}");
+        }
+    }
+
+    #[test]
+    fn overflow() {
+        assert!(eval("9999999999999999999").is_none());
+        assert!(eval("9999999999*9999999999").is_none());
+    }
+
+    #[test]
+    fn depth() {
+        let nested = |n| "(".repeat(n) + "1" + &")".repeat(n);
+        assert_eq!(eval_str(&nested(DEPTH as usize)).as_deref(), Some("1"));
+        assert!(eval(&nested(DEPTH as usize + 1)).is_none());
+
+        let negated = |n| "-".repeat(n) + "1";
+        assert_eq!(eval_str(&negated(DEPTH as usize)).as_deref(), Some("1"));
+        assert!(eval(&negated(DEPTH as usize + 1)).is_none());
+        assert!(eval(&"-".repeat(10_000)).is_none());
+        assert!(eval(&("-(".repeat(DEPTH as usize / 2) + "(1" + &")".repeat(5))).is_none());
+    }
+
+    #[test]
+    fn temperature() {
+        assert_eq!(conv_str("100 c f").as_deref(), Some("212"));
+        assert_eq!(conv_str("32 f c").as_deref(), Some("0"));
+        assert_eq!(conv_str("0 c k").as_deref(), Some("273.15"));
+        assert_eq!(conv_str("-40 f c").as_deref(), Some("-40"));
+    }
+
+    #[test]
+    fn length_and_mass() {
+        assert_eq!(conv_str("1 ft in").as_deref(), Some("12"));
+        assert_eq!(conv_str("1 mi km").as_deref(), Some("1.609"));
+        assert_eq!(conv_str("250 cm m").as_deref(), Some("2.5"));
+        assert_eq!(conv_str("1 lb g").as_deref(), Some("453.592"));
+        assert_eq!(conv_str("1 kg kg").as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn conv_errors() {
+        for args in ["", "1", "1 c", "1 c m", "1 c x", "1 c f k", "x c f"] {
+            assert!(conv(args).is_none(), "{args}");
+        }
+    }
+}
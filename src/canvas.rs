@@ -0,0 +1,96 @@
+//! 5×5 canvas shared among boards over radio.
+//!
+//! Each pixel is last-writer-wins register stamped with Lamport clock and node id,
+//! so boards applying same updates in any order end with same picture.
+
+#![deny(clippy::indexing_slicing, clippy::unwrap_used)]
+
+use crate::memory::Lattice;
+
+pub const PIXELS: usize = 25;
+
+/// Newer write wins, node id breaks ties.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Stamp {
+    pub clock: u32,
+    pub node: u32,
+}
+
+#[derive(Clone, Copy)]
+pub struct Update {
+    pub ix: u8,
+    pub on: bool,
+    pub stamp: Stamp,
+}
+
+pub struct Canvas {
+    pixels: [(bool, Stamp); PIXELS],
+    clock: u32,
+    node: u32,
+    cursor: usize,
+}
+
+impl Canvas {
+    pub const fn new() -> Self {
+        Canvas {
+            pixels: [(false, Stamp { clock: 0, node: 0 }); PIXELS],
+            clock: 0,
+            node: 0,
+            cursor: 0,
+        }
+    }
+
+    /// Own writes get stamped with `node`.
+    pub fn set_node(&mut self, node: u32) {
+        self.node = node;
+    }
+
+    /// Cursor moves left to right, top to bottom.
+    pub fn next_cursor(&mut self) {
+        self.cursor = (self.cursor + 1) % PIXELS;
+    }
+
+    /// Flips pixel under cursor, returns update for peers.
+    pub fn toggle(&mut self) -> Update {
+        // peer may have sent highest clock
+        self.clock = self.clock.saturating_add(1);
+
+        let update = Update {
+            ix: self.cursor as u8,
+            on: !self.pixels.get(self.cursor).is_some_and(|px| px.0),
+            stamp: Stamp {
+                clock: self.clock,
+                node: self.node,
+            },
+        };
+
+        self.apply(&update);
+        update
+    }
+
+    /// Peer update, stale one is ignored.
+    pub fn apply(&mut self, update: &Update) {
+        self.clock = self.clock.max(update.stamp.clock);
+
+        if let Some(px) = self.pixels.get_mut(update.ix as usize) {
+            if update.stamp > px.1 {
+                *px = (update.on, update.stamp);
+            }
+        }
+    }
+
+    /// Lit pixels full, cursor dim when `blink`.
+    pub fn lattice(&self, blink: bool) -> Lattice {
+        let mut latt = [[0; 5]; 5];
+
+        for (px, (on, _)) in latt.iter_mut().flatten().zip(&self.pixels) {
+            *px = if *on { 9 } else { 0 };
+        }
+
+        if let Some(px) = latt.iter_mut().flatten().nth(self.cursor).filter(|_| blink) {
+            *px = if *px > 0 { 3 } else { 5 };
+        }
+
+        latt
+    }
+}
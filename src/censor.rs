@@ -0,0 +1,63 @@
+//! Word filter for texts shown in public, matches become asterisks.
+//!
+//! Wordlist is kept as hashes of lowercase words, so firmware image does not spell it out.
+
+#![deny(clippy::indexing_slicing, clippy::unwrap_used)]
+
+use crate::message::{self, Message};
+
+/// `message::hash` of lowercase words.
+const WORDS: [u32; 14] = [
+    0xe677_4d4a,
+    0x893d_cdb6,
+    0xa856_eb33,
+    0x91b8_87d3,
+    0x8160_2170,
+    0x92a4_ff90,
+    0x6537_d094,
+    0x7c9a_e63f,
+    0xfd60_86e9,
+    0x50d8_74f6,
+    0x021a_eb20,
+    0x8240_5e59,
+    0x6cd5_e87b,
+    0xb99b_f986,
+];
+/// Longest word in list, longer ones are not hashed.
+const WORD_LEN: usize = 8;
+
+/// `msg` with every listed word, any letter case, made of `*`.
+pub fn apply(msg: &Message) -> Message {
+    let text = msg.as_str().as_bytes();
+    let mut out = Message::EMPTY;
+
+    let mut rest = text;
+    while !rest.is_empty() {
+        let len = rest
+            .iter()
+            .position(|b| !b.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+
+        let (word, tail) = rest.split_at(len.max(1));
+        let listed = len > 0 && listed(word);
+        for b in word {
+            _ = out.push(if listed { b'*' } else { *b });
+        }
+        rest = tail;
+    }
+
+    out
+}
+
+fn listed(word: &[u8]) -> bool {
+    if word.len() > WORD_LEN {
+        return false;
+    }
+
+    let mut lower = [0; WORD_LEN];
+    for (l, b) in lower.iter_mut().zip(word) {
+        *l = b.to_ascii_lowercase();
+    }
+    let hash = lower.get(..word.len()).map_or(0, message::hash);
+    WORDS.contains(&hash)
+}
@@ -0,0 +1,89 @@
+//! Console lines starting with `/` are commands, anything else is message.
+
+#![deny(clippy::indexing_slicing, clippy::unwrap_used)]
+
+pub enum Command<'a> {
+    Pub { topic: &'a str, payload: &'a str },
+    Sub(&'a str),
+    Unsub(&'a str),
+    /// Debug playback `normal`, `slow` or `step`.
+    Play(&'a str),
+    /// Frames to advance in step playback, 1 by default.
+    Step(u32),
+    /// Teacher opens question `id`, 1 to 255.
+    Ask(u8),
+    /// Teacher closes question.
+    End,
+    /// Check-in session `start`, `stop` or, without argument, listing.
+    Roll(&'a str),
+    /// Lists recent configuration changes.
+    Audit,
+    /// Prints counters.
+    Stats,
+    /// Prints one line snapshot of state.
+    Status,
+    /// Canvas wider than matrix, see `Viewport::command`.
+    View(&'a str),
+    /// Test pattern name, `auto` cycling them or `off`.
+    Test(&'a str),
+    /// Lists stored messages with times shown and seconds up.
+    Airtime,
+    /// Lists subsystems and whether they work.
+    Features,
+    /// Soak test `start` or `stop`.
+    Soak(&'a str),
+    /// Frame mirror `on` or `off`.
+    Mirror(&'a str),
+    /// Expression to evaluate, result scrolled too with `scroll`.
+    Calc { expr: &'a str, scroll: bool },
+    /// `<value> <from> <to>` unit conversion, result scrolled too with `scroll`.
+    Conv { args: &'a str, scroll: bool },
+    Unknown,
+}
+
+pub fn parse(line: &str) -> Option<Command<'_>> {
+    let line = line.strip_prefix('/')?;
+    let (verb, args) = line.split_once(' ').unwrap_or((line, ""));
+    let args = args.trim();
+
+    let cmd = match verb {
+        "pub" => {
+            let (topic, payload) = args.split_once(' ').unwrap_or((args, ""));
+            Command::Pub { topic, payload }
+        }
+        "sub" => Command::Sub(args),
+        "unsub" => Command::Unsub(args),
+        "play" => Command::Play(args),
+        "ask" => match args.parse() {
+            Ok(id) if id > 0 => Command::Ask(id),
+            _ => Command::Unknown,
+        },
+        "end" => Command::End,
+        "roll" => Command::Roll(args),
+        "audit" => Command::Audit,
+        "stats" => Command::Stats,
+        "status" => Command::Status,
+        "view" => Command::View(args),
+        "test" => Command::Test(args),
+        "airtime" => Command::Airtime,
+        "features" => Command::Features,
+        "soak" => Command::Soak(args),
+        "mirror" => Command::Mirror(args),
+        "calc" | "calc!" => Command::Calc {
+            expr: args,
+            scroll: verb.ends_with('!'),
+        },
+        "conv" | "conv!" => Command::Conv {
+            args,
+            scroll: verb.ends_with('!'),
+        },
+        "step" if args.is_empty() => Command::Step(1),
+        "step" => match args.parse() {
+            Ok(n) => Command::Step(n),
+            Err(_) => Command::Unknown,
+        },
+        _ => Command::Unknown,
+    };
+
+    Some(cmd)
+}
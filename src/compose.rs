@@ -0,0 +1,50 @@
+//! Stages applied on effect frames, before safety limiter and orientation.
+
+#![deny(clippy::indexing_slicing, clippy::unwrap_used)]
+
+use crate::memory::Lattice;
+
+/// Every `ANTS`th lit pixel is highlight.
+const ANTS: u32 = 4;
+/// Highlight brightness, below dimmest sparkle.
+const ANT: u8 = 2;
+
+/// Dims every `ANTS`th lit pixel, counted row by row, `phase` moves them along.
+///
+/// Mask is lit pixels of `latt`, so sparkle brightness does not move highlight.
+pub fn ants(latt: &Lattice, phase: u32) -> Lattice {
+    let mut out = *latt;
+
+    let lit = out.iter_mut().flatten().filter(|px| **px > 0);
+    for (ix, px) in (0u32..).zip(lit) {
+        if ix.wrapping_add(phase).is_multiple_of(ANTS) {
+            *px = ANT;
+        }
+    }
+    out
+}
+
+/// Trail brightness of pixel divides by this each frame.
+const DECAY: u8 = 3;
+
+/// Phosphor like trail, pixels turning off fade out over 2 frames.
+pub struct Trail {
+    prev: Lattice,
+}
+
+impl Trail {
+    pub const fn new() -> Self {
+        Trail { prev: [[0; 5]; 5] }
+    }
+
+    pub fn apply(&mut self, latt: &Lattice) -> Lattice {
+        let mut out = *latt;
+
+        for (px, prev) in out.iter_mut().flatten().zip(self.prev.iter().flatten()) {
+            *px = (*px).max(prev / DECAY);
+        }
+
+        self.prev = out;
+        out
+    }
+}
@@ -0,0 +1,162 @@
+//! Runtime configuration shared by input and animation ISRs.
+
+use crate::animation::{Kind, Sparkle};
+use crate::message::{Message, Overflow, Rotation};
+use crate::orient::Orient;
+use crate::tick;
+
+/// RTC0 ticks per frame, slowest first.
+pub const SPEEDS: [u8; 5] = [28, 18, 12, 8, 4];
+/// RTC0 ticks per frame in slow playback, 1 Hz.
+const SLOW: u8 = (tick::PER_SEC - 1) as u8;
+/// RTC0 ticks per frame in accessibility preset, half of slowest speed.
+const ACCESS: u8 = SPEEDS[0] * 2;
+/// Frames held after word in accessibility preset.
+pub const WORD_HOLD: u8 = 4;
+/// Frames held after char with stutter on.
+pub const CHAR_HOLD: u8 = 1;
+/// Steps char scrolls by, glyph and spacing after it, about.
+const CHAR_STEPS: u32 = 6;
+/// RTC0 ticks auto speed aims each message to scroll through in.
+const AUTO_TICKS: u32 = 15 * tick::PER_SEC;
+
+/// Debug playback inspecting frames on hardware.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Playback {
+    Normal,
+    Slow,
+    /// Frames shown only when stepped.
+    Step,
+}
+
+impl Playback {
+    pub fn from_name(name: &str) -> Option<Playback> {
+        match name {
+            "normal" => Some(Playback::Normal),
+            "slow" => Some(Playback::Slow),
+            "step" => Some(Playback::Step),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Config {
+    pub speed: usize,
+    pub paused: bool,
+    pub active: usize,
+    pub effect: Kind,
+    pub sparkle: Sparkle,
+    pub playback: Playback,
+    pub attract: bool,
+    /// Scrolling right, or down.
+    pub reversed: bool,
+    /// Seconds without message or button press before display sleeps, 0 never.
+    pub idle_secs: u16,
+    /// Canvas drawn instead of text, A moves cursor, B flips pixel.
+    pub canvas: bool,
+    /// Name badge, active message in passes with display off between.
+    pub badge: bool,
+    /// Buttons and gestures ignored, radio text and modes too, display never sleeps.
+    pub kiosk: bool,
+    /// Accessibility preset, steady full brightness, slow scrolling held after words.
+    pub access: bool,
+    /// Full matrix flash after each char, two after word.
+    pub pulse: bool,
+    /// Heart beating faster with more messages in last minute, instead of text.
+    pub heartbeat: bool,
+    /// Dim highlight marching along lit pixels of text.
+    pub ants: bool,
+    /// Pixels turning off fade out over few frames.
+    pub trail: bool,
+    /// Sparkle follows mood of recent messages, wider cheerful, dimmer gloomy.
+    pub mood: bool,
+    /// Scrolling hesitates once each char fully entered.
+    pub stutter: bool,
+    /// Speed follows length of each message taken to display, short ones slow.
+    pub auto_speed: bool,
+    /// Attract mode rotates to message shown least, equal airtime.
+    pub fair: bool,
+    /// Listed words in texts shown become asterisks, set only by host.
+    pub censor: bool,
+    /// Radio text goes to host for approval instead of display.
+    pub moderate: bool,
+    /// Matrix wiring.
+    pub orient: Orient,
+    /// Photosensitive safety, brightness changes ramp across all effects.
+    pub safe: bool,
+    /// New text blanks display and starts at once, not at next char boundary.
+    pub wipe: bool,
+    /// Message dropped when more arrive than RTC0 picks up.
+    pub overflow: Overflow,
+    /// Message next press, shake or attract mode switches to.
+    pub rotation: Rotation,
+}
+
+impl Config {
+    pub const DEFAULT: Config = Config {
+        speed: 1,
+        paused: false,
+        active: 0,
+        effect: Kind::Marquee,
+        sparkle: Sparkle::DEFAULT,
+        playback: Playback::Normal,
+        attract: false,
+        reversed: false,
+        idle_secs: 300,
+        canvas: false,
+        badge: false,
+        kiosk: false,
+        access: false,
+        pulse: false,
+        heartbeat: false,
+        ants: false,
+        trail: false,
+        mood: false,
+        stutter: false,
+        auto_speed: false,
+        fair: false,
+        censor: false,
+        moderate: false,
+        orient: Orient::NONE,
+        safe: false,
+        wipe: false,
+        overflow: Overflow::Oldest,
+        rotation: Rotation::RoundRobin,
+    };
+
+    pub fn divider(&self) -> u8 {
+        match self.playback {
+            Playback::Slow => SLOW,
+            _ if self.access => ACCESS,
+            _ => SPEEDS[self.speed],
+        }
+    }
+
+    /// Sparkle effects draw with.
+    pub fn lit(&self) -> Sparkle {
+        if self.access {
+            Sparkle::STEADY
+        } else {
+            self.sparkle
+        }
+    }
+
+    /// RTC0 ticks before display sleeps.
+    pub fn idle_ticks(&self) -> Option<u32> {
+        (self.idle_secs > 0).then_some(self.idle_secs as u32 * tick::PER_SEC)
+    }
+
+    pub fn next_speed(&mut self) {
+        self.speed = (self.speed + 1) % SPEEDS.len();
+    }
+
+    /// Slowest speed scrolling `text` through within `AUTO_TICKS`, fastest for longer one.
+    pub fn auto_speed(&mut self, text: &Message) {
+        let steps = text.as_str().chars().count() as u32 * CHAR_STEPS;
+        self.speed = SPEEDS
+            .iter()
+            .position(|s| steps * u32::from(*s) <= AUTO_TICKS)
+            .unwrap_or(SPEEDS.len() - 1);
+    }
+}
@@ -0,0 +1,68 @@
+//! Faults subsystems recover from, counted in one place for `/status`.
+
+#![deny(clippy::indexing_slicing, clippy::unwrap_used)]
+
+use crate::memory;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+#[derive(Clone, Copy)]
+pub enum Fault {
+    /// RTC0 stopped producing frames and was restarted.
+    Lockup,
+    /// Accelerometer did not answer sample read.
+    I2c,
+    /// Radio frame failed CRC.
+    RadioCrc,
+    /// Frame took longer than RTC0 tick.
+    Overrun,
+    /// Frame replaced before TIMER2 got to start sweep with it.
+    Skip,
+    /// Radio text dropped by rate limit or as repeat.
+    Spam,
+    /// Frames not sent, airtime budget was spent.
+    Airtime,
+}
+
+impl Fault {
+    pub const ALL: [Fault; 7] = [
+        Fault::Lockup,
+        Fault::I2c,
+        Fault::RadioCrc,
+        Fault::Overrun,
+        Fault::Skip,
+        Fault::Spam,
+        Fault::Airtime,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Fault::Lockup => "lockup",
+            Fault::I2c => "i2c",
+            Fault::RadioCrc => "crc",
+            Fault::Overrun => "overrun",
+            Fault::Skip => "skip",
+            Fault::Spam => "spam",
+            Fault::Airtime => "airtime",
+        }
+    }
+}
+
+// atomics, recording works from any interrupt without critical section
+static COUNTS: memory::Faults = [const { AtomicU32::new(0) }; Fault::ALL.len()];
+
+pub fn record(fault: Fault) {
+    if let Some(c) = COUNTS.get(fault as usize) {
+        c.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+pub fn count(fault: Fault) -> u32 {
+    COUNTS
+        .get(fault as usize)
+        .map_or(0, |c| c.load(Ordering::Relaxed))
+}
+
+/// Name and count of every fault.
+pub fn list() -> impl Iterator<Item = (&'static str, u32)> {
+    Fault::ALL.into_iter().map(|f| (f.name(), count(f)))
+}
@@ -0,0 +1,64 @@
+//! Events raised in ISRs for main loop to report.
+
+use crate::input::Press;
+
+pub const QUEUE_LEN: usize = 8;
+
+#[derive(Clone, Copy)]
+pub enum Event {
+    ButtonA(Press),
+    ButtonB(Press),
+    /// Active message should go over radio.
+    Broadcast,
+}
+
+/// Newest event gets dropped on overflow.
+pub struct Queue {
+    evts: [Option<Event>; QUEUE_LEN],
+    head: usize,
+    len: usize,
+    dropped: u32,
+}
+
+impl Queue {
+    pub const fn new() -> Self {
+        Queue {
+            evts: [None; QUEUE_LEN],
+            head: 0,
+            len: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Events lost to overflow.
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push(&mut self, evt: Event) -> bool {
+        if self.len == QUEUE_LEN {
+            self.dropped = self.dropped.wrapping_add(1);
+            return false;
+        }
+
+        self.evts[(self.head + self.len) % QUEUE_LEN] = Some(evt);
+        self.len += 1;
+        true
+    }
+
+    pub fn pop(&mut self) -> Option<Event> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let evt = self.evts[self.head].take();
+        self.head = (self.head + 1) % QUEUE_LEN;
+        self.len -= 1;
+
+        evt
+    }
+}
@@ -0,0 +1,137 @@
+//! Message char by char, each centered fading in and out.
+
+#![deny(
+    clippy::indexing_slicing,
+    clippy::unwrap_used,
+    clippy::arithmetic_side_effects
+)]
+
+use crate::{
+    animation::{Animation, Sparkle},
+    font,
+    memory::Lattice,
+    message::Message,
+};
+use microbit::hal::Rng;
+
+/// Frames of fade in, also of fade out.
+const FADE: u8 = 3;
+/// Frames at full brightness.
+const HOLD: u8 = 4;
+/// Whole char, blank frame before next one included.
+const PERIOD: u8 = FADE + HOLD + FADE + 1;
+
+pub struct Fade {
+    text: Message,
+    /// Char brightness at full level.
+    peak: Lattice,
+    /// Byte offset of current char, past text for blank period after it.
+    char_ix: usize,
+    frame: u8,
+    sparkle: Sparkle,
+    /// Char whose period last frame ended.
+    done: Option<char>,
+}
+
+impl Fade {
+    pub const fn new(text: Message, sparkle: Sparkle) -> Self {
+        Fade {
+            text,
+            peak: [[0; 5]; 5],
+            char_ix: 0,
+            frame: 0,
+            sparkle,
+            done: None,
+        }
+    }
+
+    fn load(&mut self, rng: &mut Rng) {
+        self.peak = centered(self.text.char_at(self.char_ix), self.sparkle, rng);
+    }
+
+    /// Of `FADE` steps.
+    fn level(&self) -> u8 {
+        match self.frame {
+            f if f < FADE => f.saturating_add(1),
+            f if f < FADE + HOLD => FADE,
+            f if f < PERIOD - 1 => (PERIOD - 1).saturating_sub(f),
+            _ => 0,
+        }
+    }
+}
+
+/// `c` centered, blank for `None`.
+pub fn centered(c: Option<char>, sparkle: Sparkle, rng: &mut Rng) -> Lattice {
+    let def = c.map_or(&[][..], font::col_def);
+    let off = 5usize.saturating_sub(def.len()) / 2;
+
+    let mut latt = [[0; 5]; 5];
+    for (rix, row) in latt.iter_mut().enumerate() {
+        let mask = 1 << rix;
+        for (cix, px) in row.iter_mut().enumerate() {
+            let col = cix
+                .checked_sub(off)
+                .and_then(|ix| def.get(ix))
+                .copied()
+                .unwrap_or(0);
+
+            *px = if col & mask == mask {
+                sparkle.brightness(rng)
+            } else {
+                0
+            };
+        }
+    }
+    latt
+}
+
+impl Animation for Fade {
+    fn set_text(&mut self, text: Message) {
+        self.text = text;
+        self.char_ix = 0;
+        self.frame = 0;
+    }
+
+    fn text(&self) -> &Message {
+        &self.text
+    }
+
+    fn set_sparkle(&mut self, sparkle: Sparkle) {
+        self.sparkle = sparkle;
+    }
+
+    fn reset(&mut self) {
+        self.set_text(self.text);
+    }
+
+    fn boundary(&self) -> Option<char> {
+        self.done
+    }
+
+    fn next_frame(&mut self, rng: &mut Rng) -> Lattice {
+        if self.frame == 0 {
+            self.load(rng);
+        }
+
+        let level = self.level();
+        let mut latt = self.peak;
+        for px in latt.iter_mut().flatten() {
+            *px = px.saturating_mul(level) / FADE;
+        }
+
+        self.done = None;
+        self.frame = self.frame.saturating_add(1);
+        if self.frame >= PERIOD {
+            self.frame = 0;
+            self.done = self.text.char_at(self.char_ix);
+
+            self.char_ix = match self.done {
+                Some(c) => self.char_ix.saturating_add(c.len_utf8()),
+                // blank period after text passed
+                None => 0,
+            };
+        }
+
+        latt
+    }
+}
@@ -0,0 +1,176 @@
+//! Headline feed host pushes item by item, rotated on display until items expire.
+//!
+//! Item is `/pub feed/item <id> <priority> <ttl secs> <text>`, same id replaces it,
+//! 0 ttl never expires. Higher priority goes first in each round. Items are kept in flash,
+//! ttl left counts on after power cycle, time powered off does not count.
+
+use crate::{message::Message, tick};
+
+/// RTC0 ticks each headline is shown.
+pub const ROTATE_TICKS: u32 = 15 * tick::PER_SEC;
+/// Headlines kept, lowest priority drops when full.
+pub const ITEMS: usize = 8;
+
+#[derive(Clone, Copy)]
+pub struct Item {
+    pub id: u8,
+    pub priority: u8,
+    /// Seconds to live from reception, 0 for no expiry.
+    pub ttl: u16,
+    pub text: Message,
+}
+
+impl Item {
+    /// Parses `<id> <priority> <ttl> <text>`.
+    pub fn parse(payload: &str) -> Option<Item> {
+        let mut parts = payload.splitn(4, ' ');
+
+        let id = parts.next()?.parse().ok()?;
+        let priority = parts.next()?.parse().ok()?;
+        let ttl = parts.next()?.parse().ok()?;
+        let text = parts.next().filter(|t| !t.is_empty())?;
+
+        Some(Item {
+            id,
+            priority,
+            ttl,
+            text: Message::new(text),
+        })
+    }
+}
+
+pub struct Feed {
+    /// Item and RTC0 tick it was received at, by priority descending.
+    items: [(Item, u32); ITEMS],
+    len: usize,
+    /// Item shown, `None` while feed is idle.
+    shown: Option<usize>,
+    since: u32,
+    /// Bumped when items change, not when same item is pushed again.
+    revision: u32,
+}
+
+impl Feed {
+    pub const fn new() -> Self {
+        const NONE: Item = Item {
+            id: 0,
+            priority: 0,
+            ttl: 0,
+            text: Message::EMPTY,
+        };
+
+        Feed {
+            items: [(NONE, 0); ITEMS],
+            len: 0,
+            shown: None,
+            since: 0,
+            revision: 0,
+        }
+    }
+
+    pub fn put(&mut self, item: Item, now: u32) {
+        if let Some(ix) = self.items[..self.len]
+            .iter()
+            .position(|(i, _)| i.id == item.id)
+        {
+            let (old, _) = &self.items[ix];
+            let same = old.priority == item.priority
+                && old.ttl == item.ttl
+                && old.text.as_str() == item.text.as_str();
+            if !same {
+                self.revision = self.revision.wrapping_add(1);
+            }
+            self.remove(ix);
+        } else {
+            self.revision = self.revision.wrapping_add(1);
+        }
+
+        if self.len == ITEMS {
+            if self.items[ITEMS - 1].0.priority > item.priority {
+                return;
+            }
+            self.len -= 1;
+        }
+
+        let ix = self.items[..self.len]
+            .iter()
+            .position(|(i, _)| i.priority < item.priority)
+            .unwrap_or(self.len);
+
+        self.items.copy_within(ix..self.len, ix + 1);
+        self.items[ix] = (item, now);
+        self.len += 1;
+    }
+
+    pub fn clear(&mut self) {
+        if self.len > 0 {
+            self.revision = self.revision.wrapping_add(1);
+        }
+        self.len = 0;
+    }
+
+    pub fn revision(&self) -> u32 {
+        self.revision
+    }
+
+    /// Items by priority, ttl being what is left at `now`.
+    pub fn items(&self, now: u32) -> impl Iterator<Item = Item> + '_ {
+        self.items[..self.len].iter().map(move |(item, at)| {
+            let age = (tick::elapsed(*at, now) / tick::PER_SEC).min(u16::MAX as u32) as u16;
+            let ttl = match item.ttl {
+                0 => 0,
+                ttl => ttl.saturating_sub(age).max(1),
+            };
+            Item { ttl, ..*item }
+        })
+    }
+
+    /// Text to show when headline is due, `fallback` once feed runs dry.
+    pub fn rotate(&mut self, now: u32, fallback: &Message) -> Option<Message> {
+        let mut ix = 0;
+        while ix < self.len {
+            let (item, at) = &self.items[ix];
+            if item.ttl > 0 && tick::elapsed(*at, now) >= item.ttl as u32 * tick::PER_SEC {
+                self.remove(ix);
+                self.revision = self.revision.wrapping_add(1);
+            } else {
+                ix += 1;
+            }
+        }
+
+        let Some(shown) = self.shown else {
+            if self.len == 0 {
+                return None;
+            }
+            return Some(self.show(0, now));
+        };
+
+        if self.len == 0 {
+            self.shown = None;
+            return Some(*fallback);
+        }
+
+        if tick::elapsed(self.since, now) < ROTATE_TICKS {
+            return None;
+        }
+        Some(self.show((shown + 1) % self.len, now))
+    }
+
+    fn show(&mut self, ix: usize, now: u32) -> Message {
+        self.shown = Some(ix);
+        self.since = now;
+        self.items[ix].0.text
+    }
+
+    fn remove(&mut self, ix: usize) {
+        self.items.copy_within(ix + 1..self.len, ix);
+        self.len -= 1;
+
+        // item after removed one moved in place of it
+        if let Some(shown) = self.shown.as_mut() {
+            if *shown > ix {
+                *shown -= 1;
+            }
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! Message char by char like split-flap board, each centered and flipping in from top row down.
+
+#![deny(
+    clippy::indexing_slicing,
+    clippy::unwrap_used,
+    clippy::arithmetic_side_effects
+)]
+
+use crate::{
+    animation::{Animation, Sparkle},
+    fader,
+    memory::Lattice,
+    message::Message,
+};
+use microbit::hal::Rng;
+
+/// Frames of flip, one per row.
+const FLIP: u8 = 5;
+/// Frames char stays after flip.
+const HOLD: u8 = 6;
+const PERIOD: u8 = FLIP + HOLD;
+
+pub struct Flap {
+    text: Message,
+    /// Char flipped away.
+    old: Lattice,
+    /// Char flipping in.
+    new: Lattice,
+    /// Byte offset of current char, past text for blank flap after it.
+    char_ix: usize,
+    frame: u8,
+    sparkle: Sparkle,
+    /// Char whose period last frame ended.
+    done: Option<char>,
+}
+
+impl Flap {
+    pub const fn new(text: Message, sparkle: Sparkle) -> Self {
+        Flap {
+            text,
+            old: [[0; 5]; 5],
+            new: [[0; 5]; 5],
+            char_ix: 0,
+            frame: 0,
+            sparkle,
+            done: None,
+        }
+    }
+}
+
+impl Animation for Flap {
+    /// Char shown flips away to first one of `text`.
+    fn set_text(&mut self, text: Message) {
+        self.text = text;
+        self.char_ix = 0;
+        self.frame = 0;
+    }
+
+    fn text(&self) -> &Message {
+        &self.text
+    }
+
+    fn set_sparkle(&mut self, sparkle: Sparkle) {
+        self.sparkle = sparkle;
+    }
+
+    fn reset(&mut self) {
+        self.set_text(self.text);
+        self.old = [[0; 5]; 5];
+    }
+
+    fn boundary(&self) -> Option<char> {
+        self.done
+    }
+
+    fn next_frame(&mut self, rng: &mut Rng) -> Lattice {
+        if self.frame == 0 {
+            let c = self.text.char_at(self.char_ix);
+            self.new = fader::centered(c, self.sparkle, rng);
+        }
+
+        let mut latt = self.old;
+        let flipped = usize::from(self.frame.saturating_add(1));
+        for (row, new) in latt.iter_mut().zip(&self.new).take(flipped) {
+            *row = *new;
+        }
+
+        self.done = None;
+        self.frame = self.frame.saturating_add(1);
+        if self.frame >= PERIOD {
+            self.frame = 0;
+            self.old = self.new;
+            self.done = self.text.char_at(self.char_ix);
+
+            self.char_ix = match self.done {
+                Some(c) => self.char_ix.saturating_add(c.len_utf8()),
+                // blank flap after text passed
+                None => 0,
+            };
+        }
+
+        latt
+    }
+}
@@ -0,0 +1,362 @@
+//! Settings kept over power cycles in last NVMC flash page, feed in page below backup one.
+//!
+//! Page is log of records, newest valid one wins. Page gets erased only once full,
+//! records are appended only when settings change. Page of older layout gets upgraded at boot,
+//! newest record passing through backup page so power loss midway does not lose it.
+//! Feed page is log of snapshots of all items the same way.
+
+use crate::{
+    animation::Kind,
+    feed::{self, Item},
+    message::{Message, Rotation, CAP},
+    orient::Orient,
+};
+use core::ptr::{read_volatile, write_volatile};
+use microbit::pac::NVMC;
+
+/// Last 4 KiB page of 512 KiB, far above firmware image.
+const PAGE: u32 = 0x7_F000;
+const PAGE_LEN: usize = 4096;
+/// Page below, holds migrated record while `PAGE` is rewritten.
+const BACKUP: u32 = PAGE - PAGE_LEN as u32;
+
+/// Below backup page.
+const FEED_PAGE: u32 = BACKUP - PAGE_LEN as u32;
+
+const MAGIC: u32 = 0x6d63_7532;
+const FEED_MAGIC: u32 = 0x6d63_7546;
+const VERSION: u8 = 2;
+const ERASED: u32 = 0xffff_ffff;
+
+/// `MAGIC`, then `[version][speed][effect][len]`, `[flags]`, then text.
+const RECORD_WORDS: usize = 3 + CAP / 4;
+const RECORD_LEN: usize = RECORD_WORDS * 4;
+const RECORDS: usize = PAGE_LEN / RECORD_LEN;
+
+/// Layout before flags word, `MAGIC`, then `[version][speed][effect][len]`, then text.
+const V1: u8 = 1;
+const V1_RECORD_LEN: usize = (2 + CAP / 4) * 4;
+const V1_RECORDS: usize = PAGE_LEN / V1_RECORD_LEN;
+
+/// `[id][priority][ttl]`, `[len]`, then text.
+const ITEM_WORDS: usize = 2 + CAP / 4;
+/// `FEED_MAGIC`, then `[count]`, then `feed::ITEMS` items.
+const SNAPSHOT_WORDS: usize = 2 + feed::ITEMS * ITEM_WORDS;
+const SNAPSHOT_LEN: usize = SNAPSHOT_WORDS * 4;
+const SNAPSHOTS: usize = PAGE_LEN / SNAPSHOT_LEN;
+
+const KIOSK: u8 = 1;
+/// `Orient::bits` above flags.
+const ORIENT_SHIFT: u8 = 1;
+/// Above orientation bits.
+const CENSOR: u8 = 1 << 4;
+/// `Rotation::ALL` index above censor flag.
+const ROTATION_SHIFT: u8 = 5;
+
+const _: () = assert!(
+    CAP.is_multiple_of(4) && CAP <= u8::MAX as usize,
+    "text must fill words, length fit byte"
+);
+
+#[derive(Clone, Copy)]
+pub struct Settings {
+    /// Empty for built-in default.
+    pub text: Message,
+    pub speed: usize,
+    pub effect: Kind,
+    pub kiosk: bool,
+    pub orient: Orient,
+    pub censor: bool,
+    pub rotation: Rotation,
+}
+
+impl PartialEq for Settings {
+    fn eq(&self, other: &Self) -> bool {
+        self.text.as_str() == other.text.as_str()
+            && self.speed == other.speed
+            && self.effect == other.effect
+            && self.kiosk == other.kiosk
+            && self.orient == other.orient
+            && self.censor == other.censor
+            && self.rotation == other.rotation
+    }
+}
+
+pub struct Flash {
+    nvmc: NVMC,
+    /// Record to write next, `RECORDS` when page is full.
+    next: usize,
+    /// Snapshot to write next, `SNAPSHOTS` when feed page is full.
+    feed_next: usize,
+}
+
+impl Flash {
+    /// Blocks for page erases, up to 3, when page needs migration.
+    pub fn new(nvmc: NVMC) -> Self {
+        let mut flash = Flash {
+            nvmc,
+            next: next_free(),
+            feed_next: feed_next_free(),
+        };
+        flash.migrate();
+        flash
+    }
+
+    /// Newest record, `None` when there is none or of other version.
+    pub fn load(&self) -> Option<Settings> {
+        let ix = (0..self.next).rev().find(|ix| word(*ix, 0) == MAGIC)?;
+        decode(address(ix, 0), VERSION)
+    }
+
+    /// Rewrites page of `V1` records as single current one, finishes migration cut by power loss.
+    fn migrate(&mut self) {
+        if self.next == 0 {
+            if let Some(settings) = decode(BACKUP, VERSION) {
+                self.save(&settings);
+                self.erase(BACKUP);
+            }
+            return;
+        }
+
+        if read(BACKUP) != ERASED {
+            self.erase(BACKUP);
+        }
+
+        if read(PAGE) != MAGIC || read(PAGE + 4).to_le_bytes()[0] != V1 {
+            return;
+        }
+
+        let newest = (0..V1_RECORDS)
+            .rev()
+            .map(|ix| PAGE + (ix * V1_RECORD_LEN) as u32)
+            .find(|at| read(*at) == MAGIC)
+            .and_then(|at| decode(at, V1));
+
+        // backup erased above
+        if let Some(settings) = &newest {
+            self.program(BACKUP, settings);
+        }
+
+        self.erase(PAGE);
+        self.next = 0;
+
+        if let Some(settings) = &newest {
+            self.save(settings);
+            self.erase(BACKUP);
+        }
+    }
+
+    /// Next save erases page first.
+    pub fn is_full(&self) -> bool {
+        self.next == RECORDS
+    }
+
+    /// Items of newest feed snapshot, ttl is what was left when it was saved.
+    pub fn load_feed(&self) -> impl Iterator<Item = Item> {
+        let at = (0..self.feed_next)
+            .rev()
+            .map(snapshot)
+            .find(|at| read(*at) == FEED_MAGIC);
+        let count = at.map_or(0, |at| read(at + 4).to_le_bytes()[0] as usize);
+
+        at.into_iter().flat_map(move |at| {
+            (0..count.min(feed::ITEMS)).map(move |ix| decode_item(item(at, ix)))
+        })
+    }
+
+    /// Next feed save erases feed page first.
+    pub fn is_feed_full(&self) -> bool {
+        self.feed_next == SNAPSHOTS
+    }
+
+    /// Blocks for page erase, about 85 ms, when feed page is full. Items past `feed::ITEMS` are
+    /// dropped.
+    pub fn save_feed(&mut self, items: impl Iterator<Item = Item>) {
+        if self.feed_next == SNAPSHOTS {
+            self.erase(FEED_PAGE);
+            self.feed_next = 0;
+        }
+
+        let at = snapshot(self.feed_next);
+        self.nvmc.config.write(|w| w.wen().wen());
+
+        let mut count = 0;
+        for it in items.take(feed::ITEMS) {
+            let item_at = item(at, count);
+            let [ttl_lo, ttl_hi] = it.ttl.to_le_bytes();
+            let bytes = it.text.as_str().as_bytes();
+
+            let head = [it.id, it.priority, ttl_lo, ttl_hi];
+            let len = [bytes.len() as u8, 0xff, 0xff, 0xff];
+
+            self.write(item_at, 0, u32::from_le_bytes(head));
+            self.write(item_at, 1, u32::from_le_bytes(len));
+            self.write_text(item_at + 8, bytes);
+            count += 1;
+        }
+        self.write(at, 1, u32::from_le_bytes([count as u8, 0xff, 0xff, 0xff]));
+        // magic last, torn snapshot stays invalid
+        self.write(at, 0, FEED_MAGIC);
+
+        self.nvmc.config.write(|w| w.wen().ren());
+        self.feed_next += 1;
+    }
+
+    /// Blocks for page erase, about 85 ms, when page is full.
+    pub fn save(&mut self, settings: &Settings) {
+        if self.next == RECORDS {
+            self.erase(PAGE);
+            self.next = 0;
+        }
+
+        self.program(address(self.next, 0), settings);
+        self.next += 1;
+    }
+
+    /// Writes record starting at `at`, erased.
+    fn program(&self, at: u32, settings: &Settings) {
+        let bytes = settings.text.as_str().as_bytes();
+        let effect = Kind::ALL
+            .iter()
+            .position(|k| *k == settings.effect)
+            .unwrap_or(0);
+        let head = [
+            VERSION,
+            settings.speed as u8,
+            effect as u8,
+            bytes.len() as u8,
+        ];
+
+        self.nvmc.config.write(|w| w.wen().wen());
+
+        self.write_text(at + 12, bytes);
+        let kiosk = if settings.kiosk { KIOSK } else { 0 };
+        let censor = if settings.censor { CENSOR } else { 0 };
+        let rotation = Rotation::ALL
+            .iter()
+            .position(|r| *r == settings.rotation)
+            .unwrap_or(0) as u8;
+        let flags =
+            kiosk | censor | settings.orient.bits() << ORIENT_SHIFT | rotation << ROTATION_SHIFT;
+        self.write(at, 2, u32::from_le_bytes([flags, 0xff, 0xff, 0xff]));
+        self.write(at, 1, u32::from_le_bytes(head));
+        // magic last, torn record stays invalid
+        self.write(at, 0, MAGIC);
+
+        self.nvmc.config.write(|w| w.wen().ren());
+    }
+
+    /// `CAP` bytes from `at`, `bytes` padded erased.
+    fn write_text(&self, at: u32, bytes: &[u8]) {
+        for wix in 0..CAP / 4 {
+            let mut w = [0xff; 4];
+            for (ix, b) in w.iter_mut().enumerate() {
+                *b = bytes.get(wix * 4 + ix).copied().unwrap_or(0xff);
+            }
+            self.write(at, wix, u32::from_le_bytes(w));
+        }
+    }
+
+    fn write(&self, at: u32, wix: usize, w: u32) {
+        unsafe { write_volatile((at + wix as u32 * 4) as *mut u32, w) };
+        self.wait();
+    }
+
+    fn erase(&self, page: u32) {
+        self.nvmc.config.write(|w| w.wen().een());
+        self.nvmc.erasepage().write(|w| unsafe { w.bits(page) });
+        self.wait();
+        self.nvmc.config.write(|w| w.wen().ren());
+    }
+
+    fn wait(&self) {
+        while self.nvmc.ready.read().ready().bit_is_clear() {}
+    }
+}
+
+/// Record to write next, `RECORDS` when page is full.
+fn next_free() -> usize {
+    (0..RECORDS)
+        .find(|ix| (0..RECORD_WORDS).all(|wix| word(*ix, wix) == ERASED))
+        .unwrap_or(RECORDS)
+}
+
+/// Record starting at `at`, `None` when it is not of `version`.
+fn decode(at: u32, version: u8) -> Option<Settings> {
+    if read(at) != MAGIC {
+        return None;
+    }
+
+    let [stored, speed, effect, len] = read(at + 4).to_le_bytes();
+    if stored != version {
+        return None;
+    }
+
+    // `V1` has no flags word
+    let (flags, text_at) = if version == V1 {
+        (0, at + 8)
+    } else {
+        (read(at + 8).to_le_bytes()[0], at + 12)
+    };
+
+    Some(Settings {
+        text: read_text(text_at, len),
+        speed: speed as usize,
+        effect: *Kind::ALL.get(effect as usize)?,
+        kiosk: flags & KIOSK != 0,
+        orient: Orient::from_bits(flags >> ORIENT_SHIFT),
+        censor: flags & CENSOR != 0,
+        rotation: *Rotation::ALL.get(usize::from(flags >> ROTATION_SHIFT))?,
+    })
+}
+
+/// Snapshot to write next, `SNAPSHOTS` when feed page is full.
+fn feed_next_free() -> usize {
+    (0..SNAPSHOTS)
+        .find(|ix| (0..SNAPSHOT_WORDS).all(|wix| read(snapshot(*ix) + wix as u32 * 4) == ERASED))
+        .unwrap_or(SNAPSHOTS)
+}
+
+fn snapshot(ix: usize) -> u32 {
+    FEED_PAGE + (ix * SNAPSHOT_LEN) as u32
+}
+
+/// Item `ix` of snapshot at `at`.
+fn item(at: u32, ix: usize) -> u32 {
+    at + 8 + (ix * ITEM_WORDS * 4) as u32
+}
+
+fn decode_item(at: u32) -> Item {
+    let [id, priority, ttl_lo, ttl_hi] = read(at).to_le_bytes();
+    let [len, ..] = read(at + 4).to_le_bytes();
+
+    Item {
+        id,
+        priority,
+        ttl: u16::from_le_bytes([ttl_lo, ttl_hi]),
+        text: read_text(at + 8, len),
+    }
+}
+
+/// `len` bytes from `at`, up to `CAP`, invalid UTF-8 tail dropped.
+fn read_text(at: u32, len: u8) -> Message {
+    let mut text = Message::EMPTY;
+    for bix in 0..(len as usize).min(CAP) {
+        let b = read(at + (bix / 4 * 4) as u32).to_le_bytes()[bix % 4];
+        _ = text.push(b);
+    }
+    text.validate();
+    text
+}
+
+fn address(record: usize, wix: usize) -> u32 {
+    PAGE + (record * RECORD_LEN + wix * 4) as u32
+}
+
+fn word(record: usize, wix: usize) -> u32 {
+    read(address(record, wix))
+}
+
+fn read(at: u32) -> u32 {
+    unsafe { read_volatile(at as *const u32) }
+}
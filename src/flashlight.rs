@@ -0,0 +1,159 @@
+//! Flashlight, all LEDs steady at full, toggled by triple press of B.
+//!
+//! Light takes every press while on and goes off by itself to save battery.
+
+#![deny(clippy::indexing_slicing, clippy::unwrap_used)]
+
+use crate::tick;
+
+/// Presses of B toggling light.
+const PRESSES: u8 = 3;
+/// RTC0 ticks all of them fit in.
+const WINDOW: u32 = tick::PER_SEC;
+/// Light on longer than this goes off.
+const TIMEOUT: u32 = 5 * 60 * tick::PER_SEC;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    /// Short press of B alone.
+    ShortB,
+    /// Any other press, breaks run.
+    Other,
+}
+
+pub struct Flashlight {
+    presses: u8,
+    first: u32,
+    /// Speed run started at, its short presses of B stepped it meanwhile.
+    speed: usize,
+    /// Tick light went on.
+    on: Option<u32>,
+}
+
+impl Flashlight {
+    pub const fn new() -> Self {
+        Flashlight {
+            presses: 0,
+            first: 0,
+            speed: 0,
+            on: None,
+        }
+    }
+
+    /// Counts short presses of B, `speed` before they step it. Returns whether presses are
+    /// taken, that is they toggled light, `speed` then being set back, or light is on.
+    pub fn feed(&mut self, key: Option<Key>, now: u32, speed: &mut usize) -> bool {
+        match key {
+            Some(Key::Other) => self.presses = 0,
+            Some(Key::ShortB) => {
+                if self.presses == 0 || tick::elapsed(self.first, now) > WINDOW {
+                    self.presses = 0;
+                    self.first = now;
+                    self.speed = *speed;
+                }
+
+                self.presses += 1;
+                if self.presses == PRESSES {
+                    self.presses = 0;
+                    *speed = self.speed;
+                    self.on = match self.on {
+                        Some(_) => None,
+                        None => Some(now),
+                    };
+                    return true;
+                }
+            }
+            None => {}
+        }
+
+        self.is_on(now)
+    }
+
+    pub fn is_on(&mut self, now: u32) -> bool {
+        if self.on.is_some_and(|at| tick::elapsed(at, now) >= TIMEOUT) {
+            self.on = None;
+        }
+        self.on.is_some()
+    }
+
+    /// Full brightness frame while on.
+    pub fn lattice(&mut self, now: u32) -> Option<[[u8; 5]; 5]> {
+        self.is_on(now).then_some([[9; 5]; 5])
+    }
+}
+
+impl Default for Flashlight {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEEDS: usize = 5;
+
+    /// Feeds `key` like button handler does, short press of B not taken steps speed.
+    fn press(light: &mut Flashlight, key: Key, now: u32, speed: &mut usize) -> bool {
+        let taken = light.feed(Some(key), now, speed);
+        if !taken && key == Key::ShortB {
+            *speed = (*speed + 1) % SPEEDS;
+        }
+        taken
+    }
+
+    #[test]
+    fn triple_b_keeps_speed() {
+        let mut light = Flashlight::new();
+        let mut speed = 1;
+
+        assert!(!press(&mut light, Key::ShortB, 0, &mut speed));
+        assert!(!press(&mut light, Key::ShortB, 30, &mut speed));
+        assert!(press(&mut light, Key::ShortB, 60, &mut speed));
+        assert_eq!(speed, 1);
+        assert!(light.is_on(60));
+
+        for now in [100, 130, 160] {
+            assert!(press(&mut light, Key::ShortB, now, &mut speed));
+        }
+        assert_eq!(speed, 1);
+        assert!(!light.is_on(160));
+    }
+
+    #[test]
+    fn slow_presses_step_speed() {
+        let mut light = Flashlight::new();
+        let mut speed = 1;
+
+        for now in [0, 80, 160] {
+            assert!(!press(&mut light, Key::ShortB, now, &mut speed));
+        }
+        assert_eq!(speed, 4);
+        assert!(!light.is_on(160));
+    }
+
+    #[test]
+    fn other_press_breaks_run() {
+        let mut light = Flashlight::new();
+        let mut speed = 0;
+
+        press(&mut light, Key::ShortB, 0, &mut speed);
+        press(&mut light, Key::Other, 10, &mut speed);
+        press(&mut light, Key::ShortB, 20, &mut speed);
+        assert!(!press(&mut light, Key::ShortB, 30, &mut speed));
+        assert!(!light.is_on(30));
+    }
+
+    #[test]
+    fn times_out() {
+        let mut light = Flashlight::new();
+        let mut speed = 0;
+
+        for now in [0, 10, 20] {
+            press(&mut light, Key::ShortB, now, &mut speed);
+        }
+        assert!(light.lattice(20 + TIMEOUT - 1).is_some());
+        assert!(light.lattice(20 + TIMEOUT).is_none());
+    }
+}
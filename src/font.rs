@@ -0,0 +1,51 @@
+//! Glyphs missing in ug_max, same down-top left-right column encoding.
+
+#[rustfmt::skip]
+/// Sensor readout and engineering symbols.
+pub const SYMBOLS: [&[u8]; 8] =
+[
+  /* ° */ & [0x7, 0x5, 0x7],
+  /* ± */ & [0x12, 0x17, 0x12],
+  /* ← */ & [0x4, 0xe, 0x15, 0x4, 0x4],
+  /* ↑ */ & [0x4, 0x2, 0x1f, 0x2, 0x4],
+  /* → */ & [0x4, 0x4, 0x15, 0xe, 0x4],
+  /* ↓ */ & [0x4, 0x8, 0x1f, 0x8, 0x4],
+  /* Ω */ & [0x16, 0x19, 0x1, 0x19, 0x16],
+  /* µ */ & [0x1f, 0x8, 0x8, 0xf],
+];
+
+#[rustfmt::skip]
+/// From '⁰' to '⁹', top 3 rows only but '⁸', its stacked loops take all 5.
+pub const SUPERSCRIPTS: [&[u8]; 10] =
+[
+  /* ⁰ */ & [0x7, 0x5, 0x7],
+  /* ¹ */ & [0x2, 0x7],
+  /* ² */ & [0x5, 0x5, 0x3],
+  /* ³ */ & [0x5, 0x7, 0x7],
+  /* ⁴ */ & [0x3, 0x2, 0x7],
+  /* ⁵ */ & [0x3, 0x5, 0x5],
+  /* ⁶ */ & [0x7, 0x6, 0x6],
+  /* ⁷ */ & [0x1, 0x1, 0x7],
+  /* ⁸ */ & [0x1f, 0x15, 0x1f],
+  /* ⁹ */ & [0x3, 0x3, 0x7],
+];
+
+/// Like `ug_max::col_def` but aware of `SYMBOLS` and `SUPERSCRIPTS`.
+pub fn col_def(c: char) -> &'static [u8] {
+    match c {
+        '°' => SYMBOLS[0],
+        '±' => SYMBOLS[1],
+        '←' => SYMBOLS[2],
+        '↑' => SYMBOLS[3],
+        '→' => SYMBOLS[4],
+        '↓' => SYMBOLS[5],
+        'Ω' | '\u{2126}' => SYMBOLS[6],
+        'µ' | 'μ' => SYMBOLS[7],
+        '⁰' => SUPERSCRIPTS[0],
+        '¹' => SUPERSCRIPTS[1],
+        '²' => SUPERSCRIPTS[2],
+        '³' => SUPERSCRIPTS[3],
+        '⁴'..='⁹' => SUPERSCRIPTS[c as usize - '⁰' as usize],
+        _ => ug_max::col_def(c),
+    }
+}
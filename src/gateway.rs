@@ -0,0 +1,290 @@
+//! MQTT-SN-ish publish/subscribe over serial console for host side bridges.
+//!
+//! Frames are console commands `/pub <topic> <payload>`, `/sub <filter>` and `/unsub <filter>`.
+//! Filters take MQTT `+` and `#` wildcards. Board publishes only to subscribed topics.
+//!
+//! Home Assistant discovery is published on its `homeassistant/status online` birth message.
+//! Entity topics are relative to `mcu/<node>`, bridge strips that prefix towards board.
+
+use crate::{
+    animation::Kind, config::Config, config::SPEEDS, event::Event, feed::Item, input::Press,
+    message::Message, message::Overflow, message::Rotation, orient::Orient, tick,
+};
+
+/// Topics board publishes to.
+pub const TOPICS: [&str; 4] = ["button/a", "button/b", "sensor/temperature", "chat/held"];
+const TEMPERATURE: usize = 2;
+const HELD: usize = 3;
+
+/// Ticks between die temperature samples.
+pub const TEMPERATURE_TICKS: u32 = 60 * tick::PER_SEC;
+
+/// Home Assistant component, object id and entity specific config.
+pub const ENTITIES: [(&str, &str, &str); 13] = [
+    ("text", "text", r#""name":"Text","cmd_t":"~/display/text""#),
+    (
+        "number",
+        "speed",
+        r#""name":"Speed","cmd_t":"~/display/speed","min":0,"max":4"#,
+    ),
+    (
+        "select",
+        "effect",
+        r#""name":"Effect","cmd_t":"~/display/effect","options":["marquee","fade","vertical","flap","type"]"#,
+    ),
+    (
+        "switch",
+        "pause",
+        r#""name":"Pause","cmd_t":"~/display/pause","pl_on":"on","pl_off":"off""#,
+    ),
+    (
+        "number",
+        "idle",
+        r#""name":"Sleep after","cmd_t":"~/display/idle","min":0,"max":3600,"unit_of_meas":"s""#,
+    ),
+    (
+        "switch",
+        "attract",
+        r#""name":"Attract mode","cmd_t":"~/display/attract","pl_on":"on","pl_off":"off""#,
+    ),
+    (
+        "switch",
+        "canvas",
+        r#""name":"Canvas","cmd_t":"~/display/canvas","pl_on":"on","pl_off":"off""#,
+    ),
+    (
+        "switch",
+        "badge",
+        r#""name":"Badge","cmd_t":"~/display/badge","pl_on":"on","pl_off":"off""#,
+    ),
+    (
+        "sensor",
+        "temperature",
+        r#""name":"Temperature","stat_t":"~/sensor/temperature","dev_cla":"temperature","stat_cla":"measurement","unit_of_meas":"°C""#,
+    ),
+    (
+        "device_automation",
+        "a_short",
+        r#""atype":"trigger","t":"~/button/a","pl":"short","type":"button_short_press","stype":"button_1""#,
+    ),
+    (
+        "device_automation",
+        "a_long",
+        r#""atype":"trigger","t":"~/button/a","pl":"long","type":"button_long_press","stype":"button_1""#,
+    ),
+    (
+        "device_automation",
+        "b_short",
+        r#""atype":"trigger","t":"~/button/b","pl":"short","type":"button_short_press","stype":"button_2""#,
+    ),
+    (
+        "device_automation",
+        "b_long",
+        r#""atype":"trigger","t":"~/button/b","pl":"long","type":"button_long_press","stype":"button_2""#,
+    ),
+];
+
+const _: () = assert!(SPEEDS.len() == 5, "speed entity max is 4");
+const _: () = assert!(Kind::ALL.len() == 5, "effect entity lists 5 options");
+
+/// TEMP reading in 0.25 °C, shown in °C.
+pub struct Celsius(pub i32);
+
+impl core::fmt::Display for Celsius {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let abs = self.0.unsigned_abs();
+        let sign = if self.0 < 0 { "-" } else { "" };
+        write!(f, "{sign}{}.{:02}", abs / 4, abs % 4 * 25)
+    }
+}
+
+pub enum Incoming {
+    Show(Message),
+    Feed(Item),
+    FeedClear,
+    Discover,
+    /// Change refused, settings are locked.
+    Locked,
+    Done,
+}
+
+pub struct Gateway {
+    subs: u32,
+    node: [u8; 16],
+}
+
+impl Gateway {
+    /// `device_id` from FICR makes node id.
+    pub fn new(device_id: u64) -> Self {
+        let mut node = [0; 16];
+        for (ix, n) in node.iter_mut().enumerate() {
+            let nibble = (device_id >> (60 - ix * 4)) as u8 & 0xf;
+            *n = b"0123456789abcdef"[nibble as usize];
+        }
+
+        Gateway { subs: 0, node }
+    }
+
+    pub fn node(&self) -> &str {
+        core::str::from_utf8(&self.node).unwrap_or_default()
+    }
+
+    pub fn subscribe(&mut self, filter: &str) {
+        self.subs |= mask(filter);
+    }
+
+    pub fn unsubscribe(&mut self, filter: &str) {
+        self.subs &= !mask(filter);
+    }
+
+    /// Topic and payload to publish when host subscribed.
+    pub fn publish(&self, evt: Event) -> Option<(&'static str, &'static str)> {
+        let (ix, press) = match evt {
+            Event::ButtonA(p) => (0, p),
+            Event::ButtonB(p) => (1, p),
+            Event::Broadcast => return None,
+        };
+
+        if !self.subscribed(ix) {
+            return None;
+        }
+
+        let payload = match press {
+            Press::Short => "short",
+            Press::Long => "long",
+            Press::Chord => "chord",
+            Press::LongChord => "long chord",
+        };
+
+        Some((TOPICS[ix], payload))
+    }
+
+    /// Topic and °C payload for TEMP reading `quarters`, in 0.25 °C, when host subscribed.
+    pub fn temperature(&self, quarters: i32) -> Option<(&'static str, Message)> {
+        use core::fmt::Write;
+
+        if !self.subscribed(TEMPERATURE) {
+            return None;
+        }
+
+        let mut payload = Message::new("");
+        _ = write!(payload, "{}", Celsius(quarters));
+
+        Some((TOPICS[TEMPERATURE], payload))
+    }
+
+    /// Topic radio text held for approval goes to, when host subscribed.
+    pub fn held(&self) -> Option<&'static str> {
+        self.subscribed(HELD).then_some(TOPICS[HELD])
+    }
+
+    fn subscribed(&self, ix: usize) -> bool {
+        self.subs & 1 << ix != 0
+    }
+}
+
+/// `Config` flag on/off topic sets.
+type Flag = fn(&mut Config) -> &mut bool;
+
+/// On/off `display/<name>` topics.
+const SWITCHES: [(&str, Flag); 18] = [
+    ("pause", |c| &mut c.paused),
+    ("canvas", |c| &mut c.canvas),
+    ("badge", |c| &mut c.badge),
+    ("kiosk", |c| &mut c.kiosk),
+    ("access", |c| &mut c.access),
+    ("heartbeat", |c| &mut c.heartbeat),
+    ("pulse", |c| &mut c.pulse),
+    ("ants", |c| &mut c.ants),
+    ("mood", |c| &mut c.mood),
+    ("trail", |c| &mut c.trail),
+    ("stutter", |c| &mut c.stutter),
+    ("autospeed", |c| &mut c.auto_speed),
+    ("fair", |c| &mut c.fair),
+    ("censor", |c| &mut c.censor),
+    ("moderate", |c| &mut c.moderate),
+    ("safe", |c| &mut c.safe),
+    ("wipe", |c| &mut c.wipe),
+    ("attract", |c| &mut c.attract),
+];
+
+/// Applies what host published.
+pub fn incoming(topic: &str, payload: &str, cfg: &mut Config) -> Incoming {
+    match topic {
+        "display/text" if !payload.is_empty() => return Incoming::Show(Message::new(payload)),
+        "homeassistant/status" if payload == "online" => return Incoming::Discover,
+        "feed/item" => {
+            if let Some(item) = Item::parse(payload) {
+                return Incoming::Feed(item);
+            }
+        }
+        "feed/clear" => return Incoming::FeedClear,
+        "display/speed" => {
+            if let Ok(speed) = payload.parse::<usize>() {
+                cfg.speed = speed.min(SPEEDS.len() - 1);
+            }
+        }
+        "display/effect" => {
+            if let Some(kind) = Kind::from_name(payload) {
+                cfg.effect = kind;
+            }
+        }
+        "display/idle" => {
+            if let Ok(secs) = payload.parse() {
+                cfg.idle_secs = secs;
+            }
+        }
+        "display/overflow" => {
+            if let Some(overflow) = Overflow::from_name(payload) {
+                cfg.overflow = overflow;
+            }
+        }
+        "display/rotation" => {
+            if let Some(rotation) = Rotation::from_name(payload) {
+                cfg.rotation = rotation;
+            }
+        }
+        "display/orient" => {
+            if let Some(orient) = Orient::from_name(payload) {
+                cfg.orient = orient;
+            }
+        }
+        _ => {
+            let switch = topic
+                .strip_prefix("display/")
+                .and_then(|name| SWITCHES.iter().find(|(n, _)| *n == name));
+            if let Some((_, field)) = switch {
+                match payload {
+                    "1" | "on" => *field(cfg) = true,
+                    "0" | "off" => *field(cfg) = false,
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    Incoming::Done
+}
+
+fn mask(filter: &str) -> u32 {
+    TOPICS
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| matches(filter, t))
+        .fold(0, |m, (ix, _)| m | 1 << ix)
+}
+
+fn matches(filter: &str, topic: &str) -> bool {
+    let mut f = filter.split('/');
+    let mut t = topic.split('/');
+
+    loop {
+        match (f.next(), t.next()) {
+            (Some("#"), _) => return true,
+            (Some("+"), Some(_)) => {}
+            (Some(a), Some(b)) if a == b => {}
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
@@ -0,0 +1,94 @@
+//! Ambient heart beating faster while messages keep arriving.
+
+#![deny(clippy::indexing_slicing, clippy::unwrap_used)]
+
+use crate::{memory::Lattice, tick};
+
+/// Arrivals remembered, rate saturates above.
+const ARRIVALS: usize = 8;
+/// Sliding window arrivals count in.
+const WINDOW: u32 = 60 * tick::PER_SEC;
+/// Beat period when quiet, each arrival in window shortens it.
+const IDLE_PERIOD: u32 = 3 * tick::PER_SEC;
+const MIN_PERIOD: u32 = tick::PER_SEC / 2;
+
+/// Ticks lub and dub last at least, dub starts 2 of them into beat.
+const BEAT_LEN: u32 = 8;
+
+const SHAPE: [[bool; 5]; 5] = {
+    const O: bool = false;
+    const X: bool = true;
+    [
+        [O, X, O, X, O],
+        [X, X, X, X, X],
+        [X, X, X, X, X],
+        [O, X, X, X, O],
+        [O, O, X, O, O],
+    ]
+};
+
+pub struct Heartbeat {
+    /// Arrival ticks, oldest overwritten.
+    arrivals: [Option<u32>; ARRIVALS],
+    next: usize,
+    /// Tick current beat started.
+    beat: u32,
+}
+
+impl Heartbeat {
+    pub const fn new() -> Self {
+        Heartbeat {
+            arrivals: [None; ARRIVALS],
+            next: 0,
+            beat: 0,
+        }
+    }
+
+    /// Message arrived, `now` in RTC0 ticks.
+    pub fn arrive(&mut self, now: u32) {
+        if let Some(slot) = self.arrivals.get_mut(self.next) {
+            *slot = Some(now);
+        }
+        self.next = (self.next + 1) % ARRIVALS;
+    }
+
+    /// Arrivals within `WINDOW` before `now`.
+    pub fn rate(&self, now: u32) -> usize {
+        self.arrivals
+            .iter()
+            .flatten()
+            .filter(|at| tick::elapsed(**at, now) < WINDOW)
+            .count()
+    }
+
+    /// Ticks between beats.
+    pub fn period(&self, now: u32) -> u32 {
+        (IDLE_PERIOD / (1 + self.rate(now) as u32)).max(MIN_PERIOD)
+    }
+
+    /// Heart dim between beats, full on lub, bit less on dub. Called every `frame_ticks`,
+    /// lub and dub last at least that so each lands on frame.
+    pub fn lattice(&mut self, now: u32, frame_ticks: u32) -> Lattice {
+        let len = BEAT_LEN.max(frame_ticks);
+
+        let mut into = tick::elapsed(self.beat, now);
+        if into >= self.period(now).max(4 * len) {
+            self.beat = now;
+            into = 0;
+        }
+
+        let lit = match into / len {
+            0 => 9,
+            2 => 6,
+            _ => 1,
+        };
+
+        let mut latt = [[0; 5]; 5];
+        for (px, set) in latt.iter_mut().flatten().zip(SHAPE.iter().flatten()) {
+            if *set {
+                *px = lit;
+            }
+        }
+        latt
+    }
+}
@@ -0,0 +1,125 @@
+//! Front buttons on GPIOTE channels 0 and 1, both edges.
+
+use crate::tick;
+use microbit::{
+    board::Buttons,
+    hal::gpio::{Floating, Input as InputMode, Pin},
+    hal::gpiote::Gpiote,
+    hal::prelude::*,
+    pac::GPIOTE,
+};
+
+/// Edges this close to accepted one are bounces.
+const DEBOUNCE_TICKS: u32 = 3;
+/// Press held at least this long is long.
+const LONG_TICKS: u32 = 60;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Press {
+    Short,
+    Long,
+    /// Released while other button held, release of other one is consumed.
+    Chord,
+    /// Chord held long.
+    LongChord,
+}
+
+pub struct Button {
+    pin: Pin<InputMode<Floating>>,
+    pressed: bool,
+    changed: u32,
+    /// Next release yields no press.
+    swallow: bool,
+}
+
+impl Button {
+    fn new(pin: Pin<InputMode<Floating>>) -> Self {
+        Button {
+            pin,
+            pressed: false,
+            changed: 0,
+            swallow: false,
+        }
+    }
+
+    /// Buttons are active low. Leading edge is taken, following bounces are ignored.
+    fn edge(&mut self, now: u32) -> Option<Press> {
+        let pressed = self.pin.is_low().unwrap();
+
+        if pressed == self.pressed || tick::elapsed(self.changed, now) < DEBOUNCE_TICKS {
+            return None;
+        }
+
+        let held = tick::elapsed(self.changed, now);
+        self.pressed = pressed;
+        self.changed = now;
+
+        if pressed || core::mem::take(&mut self.swallow) {
+            None
+        } else if held >= LONG_TICKS {
+            Some(Press::Long)
+        } else {
+            Some(Press::Short)
+        }
+    }
+}
+
+pub struct Input {
+    gpiote: Gpiote,
+    a: Button,
+    b: Button,
+}
+
+impl Input {
+    pub fn new(gpiote: GPIOTE, buttons: Buttons) -> Self {
+        let gpiote = Gpiote::new(gpiote);
+        let a = Button::new(buttons.button_a.degrade());
+        let b = Button::new(buttons.button_b.degrade());
+
+        gpiote.channel0().input_pin(&a.pin).toggle().enable_interrupt();
+        gpiote.channel1().input_pin(&b.pin).toggle().enable_interrupt();
+
+        Input { gpiote, a, b }
+    }
+
+    /// Buttons held now yield no press on release.
+    pub fn swallow(&mut self) {
+        for button in [&mut self.a, &mut self.b] {
+            button.swallow |= button.pressed;
+        }
+    }
+
+    /// Press of A and B released by GPIOTE event, `now` in RTC0 ticks.
+    pub fn handle(&mut self, now: u32) -> (Option<Press>, Option<Press>) {
+        let mut a = None;
+        let mut b = None;
+
+        let channel0 = self.gpiote.channel0();
+        if channel0.is_event_triggered() {
+            channel0.reset_events();
+            a = self.a.edge(now);
+        }
+
+        let channel1 = self.gpiote.channel1();
+        if channel1.is_event_triggered() {
+            channel1.reset_events();
+            b = self.b.edge(now);
+        }
+
+        let chord = |p| match p {
+            Press::Long => Press::LongChord,
+            _ => Press::Chord,
+        };
+
+        if a.is_some() && self.b.pressed {
+            a = a.map(chord);
+            self.b.swallow = true;
+        }
+        if b.is_some() && self.a.pressed {
+            b = b.map(chord);
+            self.a.swallow = true;
+        }
+
+        (a, b)
+    }
+}
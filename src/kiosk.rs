@@ -0,0 +1,73 @@
+//! Read-only kiosk, local input is ignored until hidden combo unlocks it.
+//!
+//! Combo also unlocks settings host may change only with someone at board, for a while.
+
+use crate::{input::Press, tick};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    A(Press),
+    B(Press),
+}
+
+/// Presses, in order, leaving kiosk.
+const COMBO: [Key; 4] = [
+    Key::A(Press::Long),
+    Key::B(Press::Long),
+    Key::A(Press::Short),
+    Key::B(Press::Short),
+];
+/// RTC0 ticks whole combo fits in.
+const WINDOW: u32 = 5 * tick::PER_SEC;
+/// RTC0 ticks locked settings stay open after combo.
+const OPEN_TICKS: u32 = 2 * 60 * tick::PER_SEC;
+
+pub struct Unlock {
+    matched: usize,
+    since: u32,
+    /// Tick combo was last completed at.
+    opened: Option<u32>,
+}
+
+impl Unlock {
+    pub const fn new() -> Self {
+        Unlock {
+            matched: 0,
+            since: 0,
+            opened: None,
+        }
+    }
+
+    /// Returns whether `key` completed combo.
+    pub fn feed(&mut self, key: Key, now: u32) -> bool {
+        if self.matched > 0 && tick::elapsed(self.since, now) > WINDOW {
+            self.matched = 0;
+        }
+
+        if key != COMBO[self.matched] {
+            self.matched = 0;
+            if key != COMBO[0] {
+                return false;
+            }
+        }
+
+        if self.matched == 0 {
+            self.since = now;
+        }
+
+        self.matched += 1;
+        if self.matched < COMBO.len() {
+            return false;
+        }
+
+        self.matched = 0;
+        self.opened = Some(now);
+        true
+    }
+
+    /// Whether locked settings may change at `now`.
+    pub fn is_open(&self, now: u32) -> bool {
+        self.opened
+            .is_some_and(|at| tick::elapsed(at, now) < OPEN_TICKS)
+    }
+}
@@ -0,0 +1,11 @@
+//! Parts without hardware dependencies, built for host too so their tests run there.
+//!
+//! `cargo test --lib` runs them on build machine.
+
+#![cfg_attr(not(test), no_std)]
+
+pub mod airtime;
+pub mod calc;
+pub mod flashlight;
+pub mod peers;
+pub mod tick;
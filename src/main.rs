@@ -4,20 +4,127 @@
 #[cfg(feature = "panic_halt")]
 use panic_halt as _;
 
+mod animation;
+mod attendance;
+mod attract;
+mod audit;
+mod badge;
+mod canvas;
+mod censor;
+mod command;
+mod compose;
+mod config;
+mod errors;
+mod event;
+mod fader;
+mod feed;
+mod flap;
+mod flash;
+mod font;
+mod gateway;
+mod heartbeat;
+mod input;
+mod kiosk;
+mod memory;
+mod message;
+mod mood;
+mod motion;
+mod orient;
+mod pattern;
+mod quiz;
+mod radio;
+mod safety;
+mod scroller;
+mod serial;
+mod soak;
+mod spam;
+mod typer;
+mod viewport;
+
 use core::cell::{Cell, OnceCell, RefCell};
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use errors::Fault;
+use mcu_chats_with_you_2::{airtime, calc, flashlight, peers, tick};
 use cortex_m::interrupt::free as interrupt_free;
 use cortex_m::interrupt::Mutex;
 use cortex_m_rt::entry;
 use microbit::hal::Rng;
 use microbit::{
-    display::nonblocking::{Display, GreyscaleImage},
+    display::nonblocking::{Display, Frame, GreyscaleImage, MicrobitFrame},
     hal::rtc::{Rtc, RtcInterrupt},
-    pac::{interrupt, RTC0, TIMER2},
+    pac::{interrupt, CLOCK, P0, TIMER2},
 };
 
-static DISPLAYOR: Mutex<RefCell<Option<Display<TIMER2>>>> = Mutex::new(RefCell::new(None));
-static ANIMATOR: Mutex<OnceCell<Rtc<RTC0>>> = Mutex::new(OnceCell::new());
-static RND: Mutex<Cell<Option<Rng>>> = Mutex::new(Cell::new(None));
+static DISPLAYOR: Mutex<RefCell<memory::Displayor>> = Mutex::new(RefCell::new(None));
+/// Frame taking over when TIMER2 starts next row sweep.
+static NEXT_FRAME: Mutex<Cell<memory::NextFrame>> = Mutex::new(Cell::new(None));
+static ANIMATOR: Mutex<OnceCell<memory::Animator>> = Mutex::new(OnceCell::new());
+static RND: Mutex<Cell<memory::Rnd>> = Mutex::new(Cell::new(None));
+
+static SERIAL_RX: Mutex<RefCell<memory::SerialRx>> = Mutex::new(RefCell::new(None));
+static RX_RING: Mutex<RefCell<memory::RxRing>> = Mutex::new(RefCell::new(serial::Ring::new()));
+static MSG_QUEUE: Mutex<RefCell<memory::MsgQueue>> =
+    Mutex::new(RefCell::new(message::Queue::new()));
+/// Radio text waiting for host approval.
+static HELD: Mutex<RefCell<memory::MsgQueue>> = Mutex::new(RefCell::new(message::Queue::new()));
+static STORE: Mutex<RefCell<memory::Store>> = Mutex::new(RefCell::new(message::Store::new()));
+static EFFECT: Mutex<RefCell<memory::Effect>> = Mutex::new(RefCell::new(animation::Effect::new(
+    animation::Kind::Marquee,
+    message::DEFAULT,
+    animation::Sparkle::DEFAULT,
+)));
+
+static TRANSCEIVER: Mutex<RefCell<memory::Radio>> = Mutex::new(RefCell::new(None));
+/// Budget every transmission spends, seeded at boot.
+static AIRTIME: Mutex<RefCell<memory::Airtime>> =
+    Mutex::new(RefCell::new(airtime::Airtime::new(0, 0)));
+static CANVAS: Mutex<RefCell<memory::Canvas>> = Mutex::new(RefCell::new(canvas::Canvas::new()));
+static QUIZ: Mutex<RefCell<memory::Quiz>> = Mutex::new(RefCell::new(quiz::Quiz::new()));
+static BADGE: Mutex<RefCell<memory::Badge>> = Mutex::new(RefCell::new(badge::Badge::new()));
+static FEED: Mutex<RefCell<memory::Feed>> = Mutex::new(RefCell::new(feed::Feed::new()));
+static VIEW: Mutex<RefCell<memory::Viewport>> =
+    Mutex::new(RefCell::new(viewport::Viewport::new()));
+static PEERS: Mutex<RefCell<memory::Peers>> = Mutex::new(RefCell::new(peers::Peers::new()));
+static SPAM: Mutex<RefCell<memory::Spam>> = Mutex::new(RefCell::new(spam::Filter::new()));
+static TEST: Mutex<Cell<memory::Test>> = Mutex::new(Cell::new(pattern::Test::Off));
+/// Hidden combo, leaves kiosk and opens locked settings.
+static UNLOCK: Mutex<RefCell<memory::Unlock>> = Mutex::new(RefCell::new(kiosk::Unlock::new()));
+static SOAK: Mutex<RefCell<memory::Soak>> = Mutex::new(RefCell::new(soak::Soak::new()));
+static FLASHLIGHT: Mutex<RefCell<memory::Flashlight>> =
+    Mutex::new(RefCell::new(flashlight::Flashlight::new()));
+static AUDIT: Mutex<RefCell<memory::Audit>> = Mutex::new(RefCell::new(audit::Log::new()));
+static ATTENDANCE: Mutex<RefCell<memory::Attendance>> =
+    Mutex::new(RefCell::new(attendance::Attendance::new()));
+
+static INPUT: Mutex<RefCell<memory::Input>> = Mutex::new(RefCell::new(None));
+static EVENTS: Mutex<RefCell<memory::Events>> = Mutex::new(RefCell::new(event::Queue::new()));
+static CONFIG: Mutex<Cell<memory::Config>> = Mutex::new(Cell::new(config::Config::DEFAULT));
+/// Frame on display, for mirror.
+static SHOWN_FRAME: Mutex<Cell<memory::Lattice>> = Mutex::new(Cell::new([[0; 5]; 5]));
+
+static FRAME_CNT: AtomicU32 = AtomicU32::new(0);
+static RESTART: AtomicBool = AtomicBool::new(false);
+/// Message or button press since main loop looked.
+static ACTIVITY: AtomicBool = AtomicBool::new(false);
+/// Accelerometer answered last sample.
+static MOTION: AtomicBool = AtomicBool::new(false);
+/// Frames streamed over serial.
+static MIRROR: AtomicBool = AtomicBool::new(false);
+/// Press waking display does nothing else.
+static SLEEPING: AtomicBool = AtomicBool::new(false);
+/// Badge rests with display off.
+static RESTING: AtomicBool = AtomicBool::new(false);
+/// Frames left to show in step playback.
+static STEPS: AtomicU32 = AtomicU32::new(0);
+
+/// Well above slow playback frame.
+const LOCKUP_TICKS: u32 = 2 * tick::PER_SEC;
+/// P0 pins driving matrix rows, high one is lit, row 0 first.
+const ROW_PINS: [u32; 5] = [21, 22, 15, 24, 19];
+/// Settings unchanged this long get saved.
+const SAVE_TICKS: u32 = 5 * tick::PER_SEC;
+/// Mirrored delta frames between key frames.
+const KEY_FRAMES: u32 = 50;
 
 #[entry]
 fn entry() -> ! {
@@ -26,13 +133,41 @@ fn entry() -> ! {
 
     let mut board = Board::take().unwrap();
 
-    microbit::hal::clocks::Clocks::new(board.CLOCK).start_lfclk();
-    let mut rtc0 = Rtc::new(board.RTC0, 327).unwrap();
+    // bring up in dependency order: clocks, time, storage, display, inputs, comms
+    microbit::hal::clocks::Clocks::new(board.CLOCK)
+        .enable_ext_hfosc()
+        .start_lfclk();
+
+    let mut rtc0 = Rtc::new(board.RTC0, tick::PRESCALER).unwrap();
     rtc0.enable_interrupt(RtcInterrupt::Tick, None);
     rtc0.enable_counter();
 
-    let display = Display::new(board.TIMER2, board.display_pins);
+    // Board does not hand NVMC out, nothing else uses it
+    let nvmc = unsafe { microbit::pac::Peripherals::steal().NVMC };
+    let mut flash = flash::Flash::new(nvmc);
+
+    if let Some(saved) = flash.load() {
+        update_config(None, |cfg| {
+            cfg.speed = saved.speed.min(config::SPEEDS.len() - 1);
+            cfg.effect = saved.effect;
+            cfg.kiosk = saved.kiosk;
+            cfg.censor = saved.censor;
+            cfg.rotation = saved.rotation;
+            cfg.orient = saved.orient;
 
+            if !saved.text.is_empty() {
+                cfg.active = interrupt_free(|cs| STORE.borrow(cs).borrow_mut().add(saved.text));
+            }
+        });
+    }
+
+    let now = rtc0.get_counter();
+    interrupt_free(|cs| {
+        let mut feed = FEED.borrow(cs).borrow_mut();
+        flash.load_feed().for_each(|item| feed.put(item, now));
+    });
+
+    let display = Display::new(board.TIMER2, board.display_pins);
     let rnd = Rng::new(board.RNG);
 
     interrupt_free(move |cs| {
@@ -49,7 +184,723 @@ fn entry() -> ! {
         NVIC::unmask(Interrupt::TIMER2);
     }
 
-    loop {}
+    // rest waits for first frame, marquee shows within divider ticks of power on
+    while FRAME_CNT.load(Ordering::Relaxed) == 0 {
+        cortex_m::asm::wfi();
+    }
+
+    let input = input::Input::new(board.GPIOTE, board.buttons);
+    let mut accel = motion::Accel::new(board.TWIM0, board.i2c_internal);
+
+    let (mut tx, rx) = serial::init(board.UARTE0, board.uart);
+    let radio = radio::Radio::new(board.RADIO);
+
+    interrupt_free(move |cs| {
+        SERIAL_RX.borrow(cs).replace(Some(rx));
+        INPUT.borrow(cs).replace(Some(input));
+
+        let mut transceiver = TRANSCEIVER.borrow(cs).borrow_mut();
+        *transceiver = Some(radio);
+        transceiver.as_mut().unwrap().listen();
+    });
+
+    // presses reach radio, so buttons come up with comms
+    unsafe {
+        board.NVIC.set_priority(Interrupt::UARTE0_UART0, 32);
+        board.NVIC.set_priority(Interrupt::GPIOTE, 64);
+        // below RTC0, busy air delays frames received rather than frames shown
+        board.NVIC.set_priority(Interrupt::RADIO, 96);
+
+        NVIC::unmask(Interrupt::UARTE0_UART0);
+        NVIC::unmask(Interrupt::GPIOTE);
+        NVIC::unmask(Interrupt::RADIO);
+    }
+
+    let mut line = serial::Line::new();
+    let device_id = board.FICR.deviceid[0].read().bits() as u64
+        | (board.FICR.deviceid[1].read().bits() as u64) << 32;
+    let mut gateway = gateway::Gateway::new(device_id);
+    let mut temp = microbit::hal::temp::Temp::new(board.TEMP);
+    interrupt_free(|cs| {
+        CANVAS.borrow(cs).borrow_mut().set_node(device_id as u32);
+        QUIZ.borrow(cs).borrow_mut().set_node(device_id as u32);
+        STORE.borrow(cs).borrow_mut().seed(device_id as u32);
+    });
+
+    let counter = || interrupt_free(|cs| ANIMATOR.borrow(cs).get().unwrap().get_counter());
+
+    let mut last_frame = FRAME_CNT.load(Ordering::Relaxed);
+    let mut last_tick = counter();
+
+    let mut saved = settings();
+    let mut pending = saved;
+    let mut feed_saved = interrupt_free(|cs| FEED.borrow(cs).borrow().revision());
+    let mut feed_pending = feed_saved;
+    let mut last_check = last_tick;
+    let mut attract = attract::Attract::new();
+    let mut gestures = motion::Gestures::new();
+    let mut last_sample = last_tick;
+    let mut last_temp = last_tick;
+    let mut last_active = last_tick;
+    // text last read out, only in accessibility preset
+    let mut spoken: Option<message::Message> = None;
+    // frame last mirrored and deltas since key frame
+    let mut mirrored: Option<(memory::Lattice, u32)> = None;
+    interrupt_free(|cs| {
+        AIRTIME
+            .borrow(cs)
+            .replace(airtime::Airtime::new(device_id as u32, last_tick))
+    });
+    let mut ask = airtime::Slot::new(quiz::REPEAT_TICKS, last_tick);
+    let mut beacon = airtime::Slot::new(attendance::BEACON_TICKS, last_tick);
+
+    loop {
+        // interrupt pending wakes core even inside critical section
+        interrupt_free(|cs| {
+            let idle = RX_RING.borrow(cs).borrow().is_empty()
+                && EVENTS.borrow(cs).borrow().is_empty()
+                && !ACTIVITY.load(Ordering::Relaxed);
+
+            if idle {
+                cortex_m::asm::wfi();
+            }
+        });
+
+        while let Some(b) = interrupt_free(|cs| RX_RING.borrow(cs).borrow_mut().pop()) {
+            // line missing bytes is rejected, host should resend it
+            if b == serial::GAP {
+                serial::write(&mut tx, b"!\r\n");
+            } else {
+                serial::echo(&mut tx, b);
+            }
+
+            if let Some(msg) = line.feed(b) {
+                console(&mut tx, &mut gateway, &mut temp, msg);
+            }
+        }
+
+        // host approves by publishing it to display/text, text waits until it subscribes
+        if let Some(topic) = gateway.held() {
+            while let Some(msg) = interrupt_free(|cs| HELD.borrow(cs).borrow_mut().pop()) {
+                serial::publish(&mut tx, topic, msg.as_str());
+            }
+        }
+
+        while let Some(evt) = interrupt_free(|cs| EVENTS.borrow(cs).borrow_mut().pop()) {
+            if let event::Event::Broadcast = evt {
+                interrupt_free(|cs| {
+                    let active = CONFIG.borrow(cs).get().active;
+                    let store = STORE.borrow(cs).borrow();
+                    broadcast(cs, store.get(active));
+                });
+            }
+
+            if let Some((topic, payload)) = gateway.publish(evt) {
+                serial::publish(&mut tx, topic, payload);
+            }
+        }
+
+        let shown = interrupt_free(|cs| {
+            use animation::Animation;
+
+            let access = CONFIG.borrow(cs).get().access;
+            access.then(|| *EFFECT.borrow(cs).borrow().text())
+        });
+        match (shown, spoken) {
+            (Some(text), Some(s)) if text.as_str() == s.as_str() => {}
+            (Some(text), _) => serial::announce(&mut tx, &text),
+            (None, _) => {}
+        }
+        spoken = shown;
+
+        if MIRROR.load(Ordering::Relaxed) {
+            let latt = interrupt_free(|cs| SHOWN_FRAME.borrow(cs).get());
+
+            match mirrored {
+                Some((prev, _)) if prev == latt => {}
+                Some((prev, deltas)) if deltas < KEY_FRAMES => {
+                    serial::delta_frame(&mut tx, &prev, &latt);
+                    mirrored = Some((latt, deltas + 1));
+                }
+                _ => {
+                    serial::key_frame(&mut tx, &latt);
+                    mirrored = Some((latt, 0));
+                }
+            }
+        } else {
+            mirrored = None;
+        }
+
+        let frame = FRAME_CNT.load(Ordering::Relaxed);
+        let tick = counter();
+
+        // kiosk sign has no one to wake it, idle timeout is not persisted
+        let unattended = interrupt_free(|cs| {
+            let cfg = CONFIG.borrow(cs).get();
+            cfg.badge
+                || cfg.kiosk
+                || SOAK.borrow(cs).borrow().is_on()
+                || FLASHLIGHT.borrow(cs).borrow_mut().is_on(tick)
+        }) || attract.is_on();
+        if ACTIVITY.swap(false, Ordering::Relaxed) || unattended {
+            last_active = tick;
+
+            if SLEEPING.swap(false, Ordering::Relaxed) {
+                wake();
+            }
+        } else if !SLEEPING.load(Ordering::Relaxed) {
+            let idle_ticks = interrupt_free(|cs| CONFIG.borrow(cs).get().idle_ticks());
+
+            if idle_ticks.is_some_and(|t| tick::elapsed(last_active, tick) >= t) {
+                SLEEPING.store(true, Ordering::Relaxed);
+                sleep();
+            }
+        }
+
+        if tick::elapsed(last_temp, tick) >= gateway::TEMPERATURE_TICKS {
+            last_temp = tick;
+
+            if let Some((topic, payload)) = gateway.temperature(temp.measure().to_bits()) {
+                serial::publish(&mut tx, topic, payload.as_str());
+            }
+        }
+
+        if tick::elapsed(last_sample, tick) >= motion::SAMPLE_TICKS {
+            last_sample = tick;
+
+            let sample = accel.as_mut().and_then(motion::Accel::sample);
+            MOTION.store(sample.is_some(), Ordering::Relaxed);
+            if accel.is_some() && sample.is_none() {
+                errors::record(Fault::I2c);
+            }
+
+            // tilt pans viewport instead of making gestures
+            let panned = interrupt_free(|cs| {
+                let mut view = VIEW.borrow(cs).borrow_mut();
+                let tilted = view.is_tilted();
+                if let Some(s) = sample.filter(|_| tilted) {
+                    view.tilt(s);
+                }
+                tilted
+            });
+
+            let sample = sample.filter(|_| !panned);
+            if let Some(gesture) = sample.and_then(|s| gestures.feed(s, tick)) {
+                interrupt_free(|cs| {
+                    let config = CONFIG.borrow(cs);
+                    let old = config.get();
+                    let mut cfg = old;
+                    if cfg.kiosk {
+                        return;
+                    }
+
+                    match gesture {
+                        motion::Gesture::Shake => {
+                            cfg.active = STORE
+                                .borrow(cs)
+                                .borrow_mut()
+                                .rotate(cfg.active, cfg.rotation)
+                        }
+                        motion::Gesture::TiltLeft => cfg.reversed = false,
+                        motion::Gesture::TiltRight => cfg.reversed = true,
+                    }
+
+                    config.set(cfg);
+                    log_change(cs, &old, &cfg, audit::Source::Gesture);
+                });
+            }
+        }
+
+        interrupt_free(|cs| {
+            let config = CONFIG.borrow(cs);
+            let mut cfg = config.get();
+            attract.poll(&mut cfg, &mut STORE.borrow(cs).borrow_mut(), tick);
+            config.set(cfg);
+        });
+
+        interrupt_free(|cs| {
+            if let Some(action) = SOAK.borrow(cs).borrow_mut().poll(tick) {
+                stimulate(cs, action, tick);
+            }
+        });
+
+        interrupt_free(|cs| QUIZ.borrow(cs).borrow_mut().poll(tick));
+        interrupt_free(|cs| {
+            // idle slot would spend budget on nothing
+            let repeats = QUIZ.borrow(cs).borrow().repeats();
+            if repeats && ask.due(tick, &mut AIRTIME.borrow(cs).borrow_mut()) {
+                if let Some(id) = QUIZ.borrow(cs).borrow_mut().repeat() {
+                    let borrow = TRANSCEIVER.borrow(cs);
+                    _ = borrow.borrow_mut().as_mut().unwrap().send_question(id);
+                }
+            }
+
+            if beacon.due(tick, &mut AIRTIME.borrow(cs).borrow_mut()) {
+                let borrow = TRANSCEIVER.borrow(cs);
+                _ = borrow.borrow_mut().as_mut().unwrap().send_beacon(device_id as u32);
+            }
+        });
+
+        if tick::elapsed(last_check, tick) >= SAVE_TICKS {
+            last_check = tick;
+
+            let current = settings();
+            if current == pending && current != saved && !attract.is_on() {
+                erasing(flash.is_full(), || flash.save(&current));
+                saved = current;
+            }
+            pending = current;
+
+            let feed = interrupt_free(|cs| FEED.borrow(cs).borrow().revision());
+            if feed == feed_pending && feed != feed_saved {
+                let items = interrupt_free(|cs| {
+                    let now = ANIMATOR.borrow(cs).get().unwrap().get_counter();
+                    let mut items = [None; feed::ITEMS];
+                    for (slot, item) in items.iter_mut().zip(FEED.borrow(cs).borrow().items(now)) {
+                        *slot = Some(item);
+                    }
+                    items
+                });
+                erasing(flash.is_feed_full(), || {
+                    flash.save_feed(items.into_iter().flatten())
+                });
+                feed_saved = feed;
+            }
+            feed_pending = feed;
+        }
+
+        if frame != last_frame {
+            last_frame = frame;
+            last_tick = tick;
+            continue;
+        }
+
+        if tick::elapsed(last_tick, tick) <= LOCKUP_TICKS {
+            continue;
+        }
+
+        // RTC0 stopped producing frames, blank and restart animation
+        interrupt_free(|cs| {
+            NEXT_FRAME.borrow(cs).set(None);
+            let borrow = DISPLAYOR.borrow(cs);
+            let mut refmut = borrow.borrow_mut();
+            refmut.as_mut().unwrap().clear();
+
+            let animator = ANIMATOR.borrow(cs).get().unwrap();
+            animator.reset_event(RtcInterrupt::Tick);
+            animator.enable_counter();
+        });
+
+        errors::record(Fault::Lockup);
+        RESTART.store(true, Ordering::Relaxed);
+
+        unsafe {
+            NVIC::unpend(Interrupt::RTC0);
+            NVIC::unmask(Interrupt::RTC0);
+        }
+
+        last_tick = tick;
+    }
+}
+
+fn console(
+    tx: &mut serial::Tx,
+    gateway: &mut gateway::Gateway,
+    temp: &mut microbit::hal::temp::Temp,
+    msg: message::Message,
+) {
+    use command::Command;
+    use core::fmt::Write;
+    use gateway::Incoming;
+
+    let show = match command::parse(msg.as_str()) {
+        None => {
+            interrupt_free(|cs| broadcast(cs, &msg));
+            Some(msg)
+        }
+        Some(Command::Pub { topic, payload }) => {
+            let incoming = interrupt_free(|cs| {
+                let config = CONFIG.borrow(cs);
+                let old = config.get();
+                let mut cfg = old;
+                let mut incoming = gateway::incoming(topic, payload, &mut cfg);
+                // word filter switches only with settings unlocked at board
+                let now = ANIMATOR.borrow(cs).get().unwrap().get_counter();
+                if cfg.censor != old.censor && !UNLOCK.borrow(cs).borrow().is_open(now) {
+                    cfg.censor = old.censor;
+                    incoming = Incoming::Locked;
+                }
+                config.set(cfg);
+                log_change(cs, &old, &cfg, audit::Source::Serial);
+                incoming
+            });
+
+            match incoming {
+                Incoming::Show(msg) => Some(msg),
+                Incoming::Feed(mut item) => {
+                    interrupt_free(|cs| {
+                        if CONFIG.borrow(cs).get().censor {
+                            item.text = censor::apply(&item.text);
+                        }
+                        let now = ANIMATOR.borrow(cs).get().unwrap().get_counter();
+                        FEED.borrow(cs).borrow_mut().put(item, now);
+                    });
+                    None
+                }
+                Incoming::FeedClear => {
+                    interrupt_free(|cs| FEED.borrow(cs).borrow_mut().clear());
+                    None
+                }
+                Incoming::Discover => {
+                    serial::discovery(tx, gateway.node());
+                    None
+                }
+                Incoming::Locked => {
+                    serial::write(tx, b"locked\r\n");
+                    None
+                }
+                Incoming::Done => None,
+            }
+        }
+        Some(Command::Sub(filter)) => {
+            gateway.subscribe(filter);
+            None
+        }
+        Some(Command::Unsub(filter)) => {
+            gateway.unsubscribe(filter);
+            None
+        }
+        Some(Command::Play(name)) => {
+            match config::Playback::from_name(name) {
+                Some(playback) => {
+                    update_config(Some(audit::Source::Serial), |cfg| cfg.playback = playback)
+                }
+                None => serial::write(tx, b"?\r\n"),
+            }
+            None
+        }
+        Some(Command::Step(n)) => {
+            update_config(Some(audit::Source::Serial), |cfg| {
+                cfg.playback = config::Playback::Step
+            });
+            STEPS.fetch_add(n, Ordering::Relaxed);
+            None
+        }
+        Some(Command::Ask(id)) => {
+            interrupt_free(|cs| QUIZ.borrow(cs).borrow_mut().ask(id));
+            None
+        }
+        Some(Command::End) => {
+            let tally = interrupt_free(|cs| {
+                // repeated in question slot even when this one has no airtime
+                if on_air(cs, 1) {
+                    let borrow = TRANSCEIVER.borrow(cs);
+                    _ = borrow.borrow_mut().as_mut().unwrap().send_question(0);
+                }
+                QUIZ.borrow(cs).borrow_mut().close()
+            });
+
+            if let Some((a, b)) = tally {
+                _ = write!(tx, "A {a} B {b}\r\n");
+            }
+            None
+        }
+        Some(Command::Roll(action)) => {
+            interrupt_free(|cs| {
+                let now = ANIMATOR.borrow(cs).get().unwrap().get_counter();
+                let mut attendance = ATTENDANCE.borrow(cs).borrow_mut();
+
+                match action {
+                    "start" => attendance.start(now),
+                    "stop" => attendance.stop(),
+                    "" => {
+                        for (node, secs) in attendance.list() {
+                            _ = write!(tx, "{node:08x} {secs}\r\n");
+                        }
+                    }
+                    _ => serial::write(tx, b"?\r\n"),
+                }
+            });
+            None
+        }
+        Some(Command::Audit) => {
+            interrupt_free(|cs| {
+                let now = ANIMATOR.borrow(cs).get().unwrap().get_counter();
+
+                for e in AUDIT.borrow(cs).borrow().list() {
+                    let ago = tick::elapsed(e.at, now) / tick::PER_SEC;
+                    let source = e.source.name();
+                    _ = write!(tx, "{ago}s ago {source} {}={}\r\n", e.field, e.value);
+                }
+            });
+            None
+        }
+        Some(Command::Stats) => {
+            let (rx, msg, evt) = interrupt_free(|cs| {
+                (
+                    RX_RING.borrow(cs).borrow().dropped(),
+                    MSG_QUEUE.borrow(cs).borrow().dropped(),
+                    EVENTS.borrow(cs).borrow().dropped(),
+                )
+            });
+            _ = write!(tx, "dropped rx {rx} msg {msg} evt {evt}\r\n");
+            None
+        }
+        Some(Command::Status) => {
+            interrupt_free(|cs| {
+                let now = ANIMATOR.borrow(cs).get().unwrap().get_counter();
+                let cfg = CONFIG.borrow(cs).get();
+                let mode = if SLEEPING.load(Ordering::Relaxed) {
+                    "sleep"
+                } else if QUIZ.borrow(cs).borrow().is_on() {
+                    "quiz"
+                } else if cfg.canvas {
+                    "canvas"
+                } else if cfg.badge {
+                    "badge"
+                } else if cfg.paused {
+                    "paused"
+                } else {
+                    "normal"
+                };
+                let queue = MSG_QUEUE.borrow(cs).borrow().len();
+                let frames = FRAME_CNT.load(Ordering::Relaxed);
+                let peers = PEERS.borrow(cs).borrow().count(now);
+                let temp = gateway::Celsius(temp.measure().to_bits());
+
+                // counter wraps, uptime restarts every 46 hours
+                _ = write!(
+                    tx,
+                    "up {}s mode {mode} msg {} queue {queue} frames {frames}",
+                    now / tick::PER_SEC,
+                    cfg.active,
+                );
+                _ = write!(tx, " peers {peers} temp {temp}");
+                for (name, count) in errors::list() {
+                    _ = write!(tx, " {name} {count}");
+                }
+                serial::write(tx, b"\r\n");
+            });
+            None
+        }
+        Some(Command::View(args)) => {
+            if !interrupt_free(|cs| VIEW.borrow(cs).borrow_mut().command(args)) {
+                serial::write(tx, b"?\r\n");
+            }
+            None
+        }
+        Some(Command::Test(name)) => {
+            match pattern::Test::from_name(name) {
+                Some(test) => interrupt_free(|cs| TEST.borrow(cs).set(test)),
+                None => serial::write(tx, b"?\r\n"),
+            }
+            None
+        }
+        Some(Command::Airtime) => {
+            interrupt_free(|cs| {
+                for (ix, msg, airtime) in STORE.borrow(cs).borrow().list() {
+                    let secs = airtime.ticks / tick::PER_SEC;
+                    _ = write!(tx, "{ix} {} {secs}s {}\r\n", airtime.shows, msg.as_str());
+                }
+            });
+            None
+        }
+        Some(Command::Features) => {
+            let state = |on: bool| if on { "on" } else { "off" };
+            let radio = interrupt_free(|cs| TRANSCEIVER.borrow(cs).borrow().is_some());
+            let motion = MOTION.load(Ordering::Relaxed);
+
+            // not built for board, kept in list so hosts can tell absent from unknown
+            let absent = "ble absent usb absent audio absent nfc absent";
+            _ = write!(tx, "radio {} motion {} {absent}\r\n", state(radio), state(motion));
+            None
+        }
+        Some(Command::Soak(action)) => {
+            interrupt_free(|cs| {
+                let now = ANIMATOR.borrow(cs).get().unwrap().get_counter();
+                let lockups = errors::count(Fault::Lockup);
+                let mut soak = SOAK.borrow(cs).borrow_mut();
+
+                match action {
+                    "start" => soak.start(FRAME_CNT.load(Ordering::Relaxed) ^ now, lockups, now),
+                    "stop" => {
+                        let (secs, actions, lockups) = soak.stop(lockups, now);
+                        _ = write!(tx, "{secs} s {actions} actions {lockups} lockups\r\n");
+                    }
+                    _ => serial::write(tx, b"?\r\n"),
+                }
+            });
+            None
+        }
+        Some(Command::Mirror(state)) => {
+            match state {
+                "on" => MIRROR.store(true, Ordering::Relaxed),
+                "off" => MIRROR.store(false, Ordering::Relaxed),
+                _ => serial::write(tx, b"?\r\n"),
+            }
+            None
+        }
+        Some(Command::Calc { expr, scroll }) => result(tx, calc::eval(expr), scroll),
+        Some(Command::Conv { args, scroll }) => result(tx, calc::conv(args), scroll),
+        Some(Command::Unknown) => {
+            serial::write(tx, b"?\r\n");
+            None
+        }
+    };
+
+    if let Some(msg) = show {
+        interrupt_free(|cs| {
+            let cfg = CONFIG.borrow(cs).get();
+            let msg = if cfg.censor { censor::apply(&msg) } else { msg };
+            MSG_QUEUE.borrow(cs).borrow_mut().push(msg, cfg.overflow)
+        });
+        ACTIVITY.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Prints `val`, returns it to show when `scroll`.
+fn result(
+    tx: &mut serial::Tx,
+    val: Option<calc::Fixed>,
+    scroll: bool,
+) -> Option<message::Message> {
+    use core::fmt::Write;
+
+    let Some(val) = val else {
+        serial::write(tx, b"?\r\n");
+        return None;
+    };
+
+    _ = write!(tx, "= {val}\r\n");
+    let mut msg = message::Message::EMPTY;
+    _ = write!(msg, "{val}");
+    scroll.then_some(msg)
+}
+
+/// Blanks display and stops its TIMER2 and RTC0, animation resumes on `wake`.
+/// Display, radio and HFXO off, UARTE falls back to HFINT. Buttons and serial messages still wake.
+fn sleep() {
+    interrupt_free(|cs| {
+        display_off(cs);
+        ANIMATOR.borrow(cs).get().unwrap().disable_counter();
+
+        TRANSCEIVER.borrow(cs).borrow_mut().as_mut().unwrap().sleep();
+        unsafe { (*CLOCK::ptr()).tasks_hfclkstop.write(|w| w.bits(1)) };
+    });
+}
+
+fn wake() {
+    interrupt_free(|cs| {
+        // radio needs HFXO settled, about 0.3 ms
+        let clock = unsafe { &*CLOCK::ptr() };
+        clock.events_hfclkstarted.reset();
+        clock.tasks_hfclkstart.write(|w| unsafe { w.bits(1) });
+        while clock.events_hfclkstarted.read().bits() == 0 {}
+        clock.events_hfclkstarted.reset();
+        TRANSCEIVER.borrow(cs).borrow_mut().as_mut().unwrap().wake();
+
+        display_on();
+        ANIMATOR.borrow(cs).get().unwrap().enable_counter();
+    });
+}
+
+fn display_off(cs: &cortex_m::interrupt::CriticalSection) {
+    let borrow = DISPLAYOR.borrow(cs);
+    let mut refmut = borrow.borrow_mut();
+    let display = refmut.as_mut().unwrap();
+
+    NEXT_FRAME.borrow(cs).set(None);
+    display.clear();
+    SHOWN_FRAME.borrow(cs).set([[0; 5]; 5]);
+
+    unsafe { (*TIMER2::ptr()).tasks_stop.write(|w| w.bits(1)) };
+    // driver renders only on timer events, row lit would stay on with timer stopped
+    let rows = ROW_PINS.iter().fold(0, |bits, pin| bits | 1 << pin);
+    unsafe { (*P0::ptr()).outclr.write(|w| w.bits(rows)) };
+}
+
+/// Runs `f` with display blanked when it erases flash page, erase stalls CPU.
+fn erasing(erase: bool, f: impl FnOnce()) {
+    // lit row would stay on through it
+    let dark = SLEEPING.load(Ordering::Relaxed) || RESTING.load(Ordering::Relaxed);
+    let blank = erase && !dark;
+    if blank {
+        interrupt_free(display_off);
+    }
+    f();
+    if blank {
+        display_on();
+    }
+}
+
+fn display_on() {
+    unsafe { (*TIMER2::ptr()).tasks_start.write(|w| w.bits(1)) };
+}
+
+/// Persisted part of runtime state.
+fn settings() -> flash::Settings {
+    interrupt_free(|cs| {
+        let cfg = CONFIG.borrow(cs).get();
+        let text = match cfg.active {
+            0 => message::Message::EMPTY,
+            ix => *STORE.borrow(cs).borrow().get(ix),
+        };
+
+        flash::Settings {
+            text,
+            speed: cfg.speed,
+            effect: cfg.effect,
+            kiosk: cfg.kiosk,
+            orient: cfg.orient,
+            censor: cfg.censor,
+            rotation: cfg.rotation,
+        }
+    })
+}
+
+/// Change from `source` goes to audit log.
+fn update_config(source: Option<audit::Source>, f: impl FnOnce(&mut config::Config)) {
+    interrupt_free(|cs| {
+        let config = CONFIG.borrow(cs);
+        let old = config.get();
+        let mut cfg = old;
+        f(&mut cfg);
+        config.set(cfg);
+
+        if let Some(source) = source {
+            log_change(cs, &old, &cfg, source);
+        }
+    });
+}
+
+fn log_change(
+    cs: &cortex_m::interrupt::CriticalSection,
+    old: &config::Config,
+    new: &config::Config,
+    source: audit::Source,
+) {
+    let now = ANIMATOR.borrow(cs).get().unwrap().get_counter();
+    AUDIT.borrow(cs).borrow_mut().record(old, new, source, now);
+}
+
+fn broadcast(cs: &cortex_m::interrupt::CriticalSection, msg: &message::Message) {
+    if !on_air(cs, radio::fragments(msg)) {
+        return;
+    }
+
+    let borrow = TRANSCEIVER.borrow(cs);
+    let mut refmut = borrow.borrow_mut();
+    _ = refmut.as_mut().unwrap().broadcast(msg);
+}
+
+/// Spends airtime on `frames` about to be sent, `false` when they should not be.
+fn on_air(cs: &cortex_m::interrupt::CriticalSection, frames: u32) -> bool {
+    let now = ANIMATOR.borrow(cs).get().unwrap().get_counter();
+    let granted = AIRTIME.borrow(cs).borrow_mut().take(now, frames);
+    if !granted {
+        errors::record(Fault::Airtime);
+    }
+    granted
 }
 
 #[interrupt]
@@ -57,111 +908,492 @@ fn TIMER2() {
     interrupt_free(|cs| {
         let borrow = DISPLAYOR.borrow(cs);
         let mut refmut = borrow.borrow_mut();
-        refmut.as_mut().unwrap().handle_display_event();
+        let display = refmut.as_mut().unwrap();
+
+        // compare 0 switches row, driver resets it; frame changing mid sweep tears
+        let timer = unsafe { &*TIMER2::ptr() };
+        let switch = timer.events_compare[0].read().bits() != 0;
+        // driver keeps its row to itself, pin it drives tells; sweep starts with row 1
+        let last_row = unsafe { (*P0::ptr()).out.read().bits() } & 1 << ROW_PINS[0] != 0;
+        if switch && last_row {
+            if let Some(frame) = NEXT_FRAME.borrow(cs).take() {
+                display.show_frame(&frame);
+            }
+        }
+
+        display.handle_display_event();
+    });
+}
+
+#[interrupt]
+fn UARTE0_UART0() {
+    interrupt_free(|cs| {
+        let borrow = SERIAL_RX.borrow(cs);
+        let mut refmut = borrow.borrow_mut();
+
+        if let Some(b) = serial::receive(refmut.as_mut().unwrap()) {
+            _ = RX_RING.borrow(cs).borrow_mut().push(b);
+        }
     });
 }
 
 #[interrupt]
-unsafe fn RTC0() {
-    use core::sync::atomic::{AtomicU8, Ordering};
+fn RADIO() {
+    interrupt_free(|cs| {
+        let borrow = TRANSCEIVER.borrow(cs);
+        let mut refmut = borrow.borrow_mut();
+        let kiosk = CONFIG.borrow(cs).get().kiosk;
+
+        let received = refmut.as_mut().unwrap().handle();
+        let node = match received {
+            Some(radio::Received::Text(_, from)) => from,
+            Some(radio::Received::Answer { node, .. }) => Some(node),
+            Some(radio::Received::Beacon(node)) => Some(node),
+            _ => None,
+        };
+        if let Some(node) = node {
+            let now = ANIMATOR.borrow(cs).get().unwrap().get_counter();
+            PEERS.borrow(cs).borrow_mut().heard(node, now);
+        }
+
+        match received {
+            // kiosk shows only what its host sets
+            Some(
+                radio::Received::Text(..) | radio::Received::Pixel(_) | radio::Received::Question(_),
+            ) if kiosk => {}
+            Some(radio::Received::Text(msg, from)) => {
+                let now = ANIMATOR.borrow(cs).get().unwrap().get_counter();
+                if !SPAM.borrow(cs).borrow_mut().pass(&msg, from, now) {
+                    errors::record(Fault::Spam);
+                    return;
+                }
+
+                let cfg = CONFIG.borrow(cs).get();
+                let msg = if cfg.censor { censor::apply(&msg) } else { msg };
+                let queue = if cfg.moderate { &HELD } else { &MSG_QUEUE };
+                queue.borrow(cs).borrow_mut().push(msg, cfg.overflow);
+                ACTIVITY.store(true, Ordering::Relaxed);
+            }
+            Some(radio::Received::Pixel(update)) => {
+                CANVAS.borrow(cs).borrow_mut().apply(&update);
+            }
+            Some(radio::Received::Question(id)) => {
+                let now = ANIMATOR.borrow(cs).get().unwrap().get_counter();
+                let mut quiz = QUIZ.borrow(cs).borrow_mut();
+                // answer lost for airtime goes with next repeat of question
+                if let Some((id, choice)) = quiz.question(id, now).filter(|_| on_air(cs, 1)) {
+                    _ = refmut.as_mut().unwrap().send_answer(id, choice, quiz.node());
+                }
+            }
+            Some(radio::Received::Answer { id, choice, node }) => {
+                QUIZ.borrow(cs).borrow_mut().vote(id, choice, node);
+            }
+            Some(radio::Received::Beacon(node)) => {
+                let now = ANIMATOR.borrow(cs).get().unwrap().get_counter();
+                ATTENDANCE.borrow(cs).borrow_mut().heard(node, now);
+            }
+            None => {}
+        }
+    });
+}
+
+#[interrupt]
+fn GPIOTE() {
+    interrupt_free(|cs| {
+        let now = ANIMATOR.borrow(cs).get().unwrap().get_counter();
+
+        let borrow = INPUT.borrow(cs);
+        let mut refmut = borrow.borrow_mut();
+        let input = refmut.as_mut().unwrap();
+        let (a, b) = input.handle(now);
+
+        ACTIVITY.store(true, Ordering::Relaxed);
+        if SLEEPING.load(Ordering::Relaxed) {
+            input.swallow();
+            return;
+        }
+
+        presses(cs, a, b, audit::Source::Button, now);
+    });
+}
+
+/// Soak `action` as if user or host took it.
+fn stimulate(cs: &cortex_m::interrupt::CriticalSection, action: soak::Action, now: u32) {
+    use input::Press;
+    use soak::Action;
+
+    let source = audit::Source::Soak;
+    let config = CONFIG.borrow(cs);
+    let old = config.get();
+    let mut cfg = old;
+
+    match action {
+        Action::PressA => return presses(cs, Some(Press::Short), None, source, now),
+        Action::PressB => return presses(cs, None, Some(Press::Short), source, now),
+        Action::LongA => return presses(cs, Some(Press::Long), None, source, now),
+        Action::Message(ix) => {
+            let msg = soak::MESSAGES[ix % soak::MESSAGES.len()];
+            MSG_QUEUE.borrow(cs).borrow_mut().push(msg, cfg.overflow);
+            return;
+        }
+        Action::Effect => cfg.effect = cfg.effect.next(),
+        Action::Speed => cfg.next_speed(),
+        Action::Pause => cfg.paused = !cfg.paused,
+        Action::NextMessage => {
+            cfg.active = STORE
+                .borrow(cs)
+                .borrow_mut()
+                .rotate(cfg.active, cfg.rotation)
+        }
+        Action::Reverse => cfg.reversed = !cfg.reversed,
+        Action::Canvas => cfg.canvas = !cfg.canvas,
+    }
+
+    config.set(cfg);
+    log_change(cs, &old, &cfg, source);
+}
+
+/// Acts on presses of A and B, from buttons or simulated by `source`.
+fn presses(
+    cs: &cortex_m::interrupt::CriticalSection,
+    a: Option<input::Press>,
+    b: Option<input::Press>,
+    source: audit::Source,
+    now: u32,
+) {
+    use input::Press;
+    use kiosk::Key;
+
+    let config = CONFIG.borrow(cs);
+    let old = config.get();
+    let mut cfg = old;
+
+    // combo counts outside kiosk too, it opens locked settings
+    let keys = [a.map(Key::A), b.map(Key::B)];
+    let mut unlock = UNLOCK.borrow(cs).borrow_mut();
+    let unlocked = keys.into_iter().flatten().any(|k| unlock.feed(k, now));
+    drop(unlock);
+
+    if cfg.kiosk {
+        if unlocked {
+            cfg.kiosk = false;
+            config.set(cfg);
+            log_change(cs, &old, &cfg, source);
+        }
+        return;
+    }
+
+    // speed short presses of B stepped is set back when they toggle light
+    let key = match (a, b) {
+        (None, None) => None,
+        (None, Some(Press::Short)) => Some(flashlight::Key::ShortB),
+        _ => Some(flashlight::Key::Other),
+    };
+    if FLASHLIGHT.borrow(cs).borrow_mut().feed(key, now, &mut cfg.speed) {
+        config.set(cfg);
+        log_change(cs, &old, &cfg, source);
+        return;
+    }
+
+    // press only leaves attract mode
+    if cfg.attract && (a.is_some() || b.is_some()) {
+        cfg.attract = false;
+        config.set(cfg);
+        log_change(cs, &old, &cfg, source);
+        return;
+    }
+
+    if a == Some(Press::Long) || b == Some(Press::Long) {
+        cfg.paused = !cfg.paused;
+    }
+
+    if a == Some(Press::LongChord) || b == Some(Press::LongChord) {
+        cfg.access = !cfg.access;
+    }
+
+    let mut quiz = QUIZ.borrow(cs).borrow_mut();
+
+    if quiz.is_on() {
+        let choice = match (a, b) {
+            (Some(Press::Short), _) => Some(quiz::A),
+            (_, Some(Press::Short)) => Some(quiz::B),
+            _ => None,
+        };
+
+        if let Some((id, choice)) = choice.and_then(|c| quiz.answer(c)).filter(|_| on_air(cs, 1)) {
+            let borrow = TRANSCEIVER.borrow(cs);
+            _ = borrow.borrow_mut().as_mut().unwrap().send_answer(id, choice, quiz.node());
+        }
+    } else if cfg.canvas {
+        let mut canvas = CANVAS.borrow(cs).borrow_mut();
+
+        if a == Some(Press::Short) {
+            canvas.next_cursor();
+        }
+
+        if b == Some(Press::Short) {
+            let update = canvas.toggle();
+            if on_air(cs, 1) {
+                let borrow = TRANSCEIVER.borrow(cs);
+                _ = borrow.borrow_mut().as_mut().unwrap().send_pixel(&update);
+            }
+        }
+    } else {
+        if a == Some(Press::Short) {
+            cfg.active = STORE.borrow(cs).borrow_mut().rotate(cfg.active, cfg.rotation);
+        }
+
+        if b == Some(Press::Short) {
+            cfg.next_speed();
+        }
+    }
+
+    config.set(cfg);
+    log_change(cs, &old, &cfg, source);
+
+    let mut events = EVENTS.borrow(cs).borrow_mut();
+    if let Some(p) = a {
+        _ = events.push(event::Event::ButtonA(p));
+    }
+    if let Some(p) = b {
+        _ = events.push(event::Event::ButtonB(p));
+    }
+    if a == Some(Press::Chord) || b == Some(Press::Chord) {
+        _ = events.push(event::Event::Broadcast);
+    }
+}
+
+#[interrupt]
+fn RTC0() {
+    use animation::{Animation, Effect};
+    use core::sync::atomic::{AtomicU8, AtomicUsize};
+
+    static SCALER: AtomicU8 = AtomicU8::new(0);
+    static SHOWN: AtomicUsize = AtomicUsize::new(0);
+    /// Frames left to hold after word.
+    static HOLD: AtomicU8 = AtomicU8::new(0);
+    /// Flashlight was on last frame.
+    static LIGHT: AtomicBool = AtomicBool::new(false);
+    /// Pulse frames left, lit ones set, least significant first, above leading 1.
+    static PULSE: AtomicU8 = AtomicU8::new(1);
+    static LIMITER: Mutex<RefCell<memory::Limiter>> =
+        Mutex::new(RefCell::new(safety::Limiter::new()));
+    static TRAIL: Mutex<RefCell<memory::Trail>> =
+        Mutex::new(RefCell::new(compose::Trail::new()));
+    static HEART: Mutex<RefCell<memory::Heartbeat>> =
+        Mutex::new(RefCell::new(heartbeat::Heartbeat::new()));
+    static MOOD: Mutex<RefCell<memory::Mood>> = Mutex::new(RefCell::new(mood::Mood::new()));
+    /// Text taking over at next char boundary.
+    static PENDING: Mutex<Cell<memory::Pending>> = Mutex::new(Cell::new(None));
 
     interrupt_free(|cs| {
         let animator = ANIMATOR.borrow(cs).get().unwrap();
         animator.reset_event(RtcInterrupt::Tick);
     });
 
-    static mut COL_DEF_IX: usize = 0;
-    static mut COL_IX: usize = 0;
+    if RESTART.swap(false, Ordering::Relaxed) {
+        SCALER.store(0, Ordering::Relaxed);
+        interrupt_free(|cs| EFFECT.borrow(cs).borrow_mut().reset());
+    }
 
-    static mut DISP_LATT: [[u8; 5]; 5] = [
-        [0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0],
-        [0, 0, 0, 0, 0],
-    ];
+    let cfg = interrupt_free(|cs| CONFIG.borrow(cs).get());
 
-    static mut SCALER: AtomicU8 = AtomicU8::new(0);
-    static mut INS_SP: AtomicU8 = AtomicU8::new(0);
+    if SCALER.fetch_add(1, Ordering::Relaxed) < cfg.divider() {
+        return;
+    }
 
-    const TEXT: &str = "software9119.technology";
-    const TEXT_PTR: *const u8 = TEXT.as_ptr();
+    SCALER.store(0, Ordering::Relaxed);
+    FRAME_CNT.fetch_add(1, Ordering::Relaxed);
+
+    // flashlight shows through pause, frame after it goes off too
+    let light = interrupt_free(|cs| {
+        let now = ANIMATOR.borrow(cs).get().unwrap().get_counter();
+        FLASHLIGHT.borrow(cs).borrow_mut().lattice(now)
+    });
+    let bypass = LIGHT.swap(light.is_some(), Ordering::Relaxed) || light.is_some();
 
-    if SCALER.fetch_add(1, Ordering::Relaxed) < 18 {
+    if !bypass
+        && (cfg.paused
+            || HOLD
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+                .is_ok())
+    {
         return;
-    } else {
-        SCALER.swap(0, Ordering::Relaxed);
     }
 
-    for cix in 1..5 {
-        let prev_cix = cix - 1;
-        for rix in 0..5 {
-            DISP_LATT[rix][prev_cix] = DISP_LATT[rix][cix];
-        }
+    if !bypass
+        && cfg.playback == config::Playback::Step
+        && STEPS
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+            .is_err()
+    {
+        return;
     }
 
-    let ins_sp = INS_SP.load(Ordering::Relaxed);
+    let step = interrupt_free(|cs| {
+        let now = ANIMATOR.borrow(cs).get().unwrap().get_counter();
+        let mut badge = BADGE.borrow(cs).borrow_mut();
 
-    let def = if ins_sp > 0 {
-        &ug_max::SPACING
-    } else {
-        ug_max::col_def(TEXT_PTR.offset(COL_DEF_IX as isize).read() as char)
-    };
+        if !cfg.badge {
+            badge.reset();
+            return None;
+        }
+        Some(badge.step(now))
+    });
 
-    let col = def[COL_IX];
+    let rest = light.is_none() && matches!(step, Some(badge::Step::Rest));
+    if rest != RESTING.swap(rest, Ordering::Relaxed) {
+        if rest {
+            interrupt_free(display_off);
+        } else {
+            display_on();
+        }
+    }
+    if rest {
+        return;
+    }
 
     let mut rnd = interrupt_free(|cs| {
         let borrow = RND.borrow(cs);
         borrow.take().unwrap()
     });
 
-    for rix in 0..5 {
-        let mask = 1 << rix;
-
-        let brightness = if col & mask == mask {
-            let rnd = rnd.random_u8() % 10;
+    interrupt_free(|cs| {
+        let borrow = EFFECT.borrow(cs);
+        let mut effect = borrow.borrow_mut();
 
-            match rnd {
-                0..=5 => 5,
-                x => x,
-            }
-        } else {
-            0
-        };
+        let mut store = STORE.borrow(cs).borrow_mut();
+        let config = CONFIG.borrow(cs);
+        let mut cfg = config.get();
 
-        DISP_LATT[rix][4] = brightness;
-    }
+        if effect.kind() != cfg.effect {
+            *effect = Effect::new(cfg.effect, *store.get(cfg.active), cfg.lit());
+        }
 
-    let gsi = GreyscaleImage::new(&DISP_LATT);
+        let now = ANIMATOR.borrow(cs).get().unwrap().get_counter();
+        let mut next = FEED.borrow(cs).borrow_mut().rotate(now, store.get(cfg.active));
 
-    interrupt_free(|cs| {
-        let rnd_borrow = RND.borrow(cs);
-        rnd_borrow.set(Some(rnd));
+        if let Some(msg) = MSG_QUEUE.borrow(cs).borrow_mut().pop() {
+            cfg.active = store.add(msg);
+            config.set(cfg);
+            next = Some(*store.get(cfg.active));
+            SHOWN.store(cfg.active, Ordering::Relaxed);
+            store.count_show(cfg.active);
+            HEART.borrow(cs).borrow_mut().arrive(now);
+            MOOD.borrow(cs).borrow_mut().feed(&msg, now);
+        } else if cfg.active != SHOWN.load(Ordering::Relaxed) {
+            next = Some(*store.get(cfg.active));
+            SHOWN.store(cfg.active, Ordering::Relaxed);
+            store.count_show(cfg.active);
+        }
 
-        let dis_borrow = DISPLAYOR.borrow(cs);
-        let mut refmut = dis_borrow.borrow_mut();
-        refmut.as_mut().unwrap().show(&gsi);
-    });
+        if let Some(text) = next.filter(|_| cfg.auto_speed) {
+            cfg.auto_speed(&text);
+            config.set(cfg);
+        }
 
-    COL_IX += 1;
-    if COL_IX == def.len() {
-        COL_IX = 0;
+        let pending = PENDING.borrow(cs);
+        match next {
+            Some(text) if cfg.wipe => {
+                effect.set_text(text);
+                effect.reset();
+                pending.set(None);
+            }
+            Some(text) => pending.set(Some(text)),
+            None => {}
+        }
 
-        let sp = if ins_sp == 0 {
-            COL_DEF_IX += 1;
+        let lit = if cfg.mood && !cfg.access {
+            MOOD.borrow(cs).borrow_mut().sparkle(cfg.lit(), now)
+        } else {
+            cfg.lit()
+        };
+        effect.set_sparkle(lit);
+        effect.set_reversed(cfg.reversed);
+        if let Some(badge::Step::Text { restart: true }) = step {
+            effect.reset();
+        }
 
-            if COL_DEF_IX == TEXT.len() {
-                COL_DEF_IX = 0;
-                5
+        let test = TEST.borrow(cs).get();
+        let latt = if let Some(latt) = light {
+            latt
+        } else if let Some(latt) = test.lattice(FRAME_CNT.load(Ordering::Relaxed)) {
+            latt
+        } else if let Some(badge::Step::Intro(latt)) = step {
+            latt
+        } else if let Some(latt) = QUIZ.borrow(cs).borrow().lattice() {
+            latt
+        } else if VIEW.borrow(cs).borrow().is_on() {
+            VIEW.borrow(cs).borrow_mut().lattice()
+        } else if cfg.canvas {
+            let blink = FRAME_CNT.load(Ordering::Relaxed) & 1 == 1;
+            CANVAS.borrow(cs).borrow().lattice(blink)
+        } else if cfg.heartbeat {
+            let frame_ticks = u32::from(cfg.divider()) + 1;
+            HEART.borrow(cs).borrow_mut().lattice(now, frame_ticks)
+        } else if let Ok(p) =
+            PULSE.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |p| (p > 1).then_some(p >> 1))
+        {
+            let px = if p & 1 == 1 { 9 } else { 0 };
+            [[px; 5]; 5]
+        } else {
+            let frame = effect.next_frame(&mut rnd);
+            let boundary = effect.boundary();
+            store.count_ticks(cfg.active, u32::from(cfg.divider()) + 1);
+            let frame = if cfg.ants {
+                compose::ants(&frame, FRAME_CNT.load(Ordering::Relaxed))
+            } else {
+                frame
+            };
+            let frame = if cfg.trail {
+                TRAIL.borrow(cs).borrow_mut().apply(&frame)
             } else {
-                1
+                frame
+            };
+
+            // text swaps between glyphs, never mid one, empty text has none
+            if boundary.is_some() || effect.text().is_empty() {
+                if let Some(text) = pending.take() {
+                    effect.set_text(text);
+                }
+            }
+
+            if cfg.access && boundary == Some(' ') {
+                HOLD.store(config::WORD_HOLD, Ordering::Relaxed);
+            } else if cfg.stutter && boundary.is_some() {
+                HOLD.store(config::CHAR_HOLD, Ordering::Relaxed);
             }
+
+            // single flash after char, double after word
+            match boundary {
+                Some(' ') if cfg.pulse => PULSE.store(0b1101, Ordering::Relaxed),
+                Some(_) if cfg.pulse => PULSE.store(0b11, Ordering::Relaxed),
+                _ => {}
+            }
+            frame
+        };
+        let latt = if cfg.safe {
+            LIMITER.borrow(cs).borrow_mut().apply(&latt, now)
         } else {
-            ins_sp - 1
+            latt
         };
+        let latt = cfg.orient.apply(&latt);
+        SHOWN_FRAME.borrow(cs).set(latt);
+        let mut frame = MicrobitFrame::default();
+        frame.set(&GreyscaleImage::new(&latt));
+        // display starved, last whole frame stays up rather than torn one
+        if NEXT_FRAME.borrow(cs).replace(Some(frame)).is_some() {
+            errors::record(Fault::Skip);
+        }
 
-        INS_SP.store(sp, Ordering::Relaxed);
-    }
+        let rnd_borrow = RND.borrow(cs);
+        rnd_borrow.set(Some(rnd));
+
+        // next tick came while frame was being made
+        if ANIMATOR.borrow(cs).get().unwrap().is_event_triggered(RtcInterrupt::Tick) {
+            errors::record(Fault::Overrun);
+        }
+    });
 }
 
 #[cfg(feature = "panic_abort")]
@@ -0,0 +1,141 @@
+//! Ledger of static memory checked against nRF52833 RAM at compile time.
+//!
+//! Every `static` must be counted in `TOTAL`, all but atomics of main.rs take their type from here.
+
+use crate::{
+    airtime, animation, attendance, audit, badge, canvas, compose, config, errors, event, feed,
+    flashlight, heartbeat, input, kiosk, message, mood, pattern, peers, quiz, radio, safety,
+    serial, soak, spam, viewport,
+};
+use core::cell::{OnceCell, RefCell};
+use core::mem::size_of;
+use core::sync::atomic::{AtomicBool, AtomicU32, AtomicU8, AtomicUsize};
+use microbit::{
+    display::nonblocking::{Display, MicrobitFrame},
+    hal::{rtc::Rtc, Rng},
+    pac::{RTC0, TIMER2},
+};
+
+/// nRF52833 RAM.
+pub const RAM: usize = 128 * 1024;
+/// Stack, HAL and everything not listed in `TOTAL`.
+pub const RESERVE: usize = 16 * 1024;
+
+/// Display driver including its frame buffer.
+pub type Displayor = Option<Display<TIMER2>>;
+/// Frame staged for TIMER2.
+pub type NextFrame = Option<MicrobitFrame>;
+/// Greyscale lattice, one on display kept for mirror. Effects, limiter and trail hold
+/// theirs inside own types.
+pub type Lattice = [[u8; 5]; 5];
+/// Effect state including its lattices and message.
+pub type Effect = animation::Effect;
+/// RTC0 counting ticks for everyone.
+pub type Animator = Rtc<RTC0>;
+/// Taken by RTC0 for sparkle.
+pub type Rnd = Option<Rng>;
+/// UARTE0 receiver, ISR fills `RxRing` from it.
+pub type SerialRx = Option<serial::Rx>;
+/// UARTE0 reception, drained by main loop.
+pub type RxRing = serial::Ring;
+/// Lines waiting for RTC0 to pick up, and radio text held for approval.
+pub type MsgQueue = message::Queue;
+/// Text RTC0 swaps in at next char boundary.
+pub type Pending = Option<message::Message>;
+/// Buttons on GPIOTE.
+pub type Input = Option<input::Input>;
+/// Hidden combo leaving kiosk.
+pub type Unlock = kiosk::Unlock;
+/// Button and gesture reports for main loop.
+pub type Events = event::Queue;
+/// Runtime configuration.
+pub type Config = config::Config;
+/// Messages cycled with button A.
+pub type Store = message::Store;
+/// Radio including its DMA buffer and transmit queue.
+pub type Radio = Option<radio::Radio>;
+/// Budget every transmission spends.
+pub type Airtime = airtime::Airtime;
+/// Canvas shared over radio.
+pub type Canvas = canvas::Canvas;
+/// Votes tallied by teacher board.
+pub type Quiz = quiz::Quiz;
+/// Boards heard during check-in session.
+pub type Attendance = attendance::Attendance;
+/// Recent configuration changes.
+pub type Audit = audit::Log;
+/// Headlines pushed by host.
+pub type Feed = feed::Feed;
+/// Canvas larger than matrix.
+pub type Viewport = viewport::Viewport;
+/// Name badge passes.
+pub type Badge = badge::Badge;
+/// Radio boards heard lately.
+pub type Peers = peers::Peers;
+/// Radio text senders and recent hashes.
+pub type Spam = spam::Filter;
+/// Display test pattern.
+pub type Test = pattern::Test;
+/// Soak test state.
+pub type Soak = soak::Soak;
+/// Flashlight toggle and timeout.
+pub type Flashlight = flashlight::Flashlight;
+/// Fault counters, one per `errors::Fault`.
+pub type Faults = [AtomicU32; errors::Fault::ALL.len()];
+/// Photosensitive safety stage of RTC0 frames.
+pub type Limiter = safety::Limiter;
+/// Fading stage of RTC0 frames, keeps previous one.
+pub type Trail = compose::Trail;
+/// Heart shown instead of text, with arrivals it beats to.
+pub type Heartbeat = heartbeat::Heartbeat;
+/// Chat mood shifting sparkle.
+pub type Mood = mood::Mood;
+
+/// Atomic flags and counters in main.rs, by type.
+const ATOMICS: usize = size_of::<AtomicU32>() * 2
+    + size_of::<AtomicBool>() * 7
+    + size_of::<AtomicU8>() * 3
+    + size_of::<AtomicUsize>();
+
+/// `T` behind `RefCell`, borrow flag included.
+const fn shared<T>() -> usize {
+    size_of::<RefCell<T>>()
+}
+
+pub const TOTAL: usize = shared::<Displayor>()
+    + size_of::<NextFrame>()
+    + size_of::<Lattice>()
+    + shared::<Effect>()
+    + size_of::<OnceCell<Animator>>()
+    + size_of::<Rnd>()
+    + shared::<SerialRx>()
+    + shared::<RxRing>()
+    + shared::<MsgQueue>() * 2
+    + size_of::<Pending>()
+    + shared::<Input>()
+    + shared::<Unlock>()
+    + shared::<Events>()
+    + size_of::<Config>()
+    + shared::<Store>()
+    + shared::<Radio>()
+    + shared::<Airtime>()
+    + shared::<Canvas>()
+    + shared::<Quiz>()
+    + shared::<Attendance>()
+    + shared::<Feed>()
+    + shared::<Viewport>()
+    + shared::<Badge>()
+    + shared::<Peers>()
+    + shared::<Spam>()
+    + size_of::<Test>()
+    + shared::<Soak>()
+    + shared::<Flashlight>()
+    + size_of::<Faults>()
+    + shared::<Limiter>()
+    + shared::<Trail>()
+    + shared::<Heartbeat>()
+    + shared::<Mood>()
+    + shared::<Audit>()
+    + ATOMICS;
+
+const _: () = assert!(TOTAL <= RAM - RESERVE, "static buffers exceed RAM budget");
@@ -0,0 +1,349 @@
+//! Fixed capacity UTF-8 messages, queue handing them over to scroller and store of known ones.
+
+pub const CAP: usize = 128;
+pub const QUEUE_LEN: usize = 4;
+pub const SLOTS: usize = 4;
+
+pub const DEFAULT: Message = Message::new("software9119.technology");
+
+#[derive(Clone, Copy)]
+pub struct Message {
+    buf: [u8; CAP],
+    len: usize,
+}
+
+impl Message {
+    pub const EMPTY: Message = Message {
+        buf: [0; CAP],
+        len: 0,
+    };
+
+    /// Truncates to `CAP` bytes, `text` should be ASCII when longer.
+    pub const fn new(text: &str) -> Self {
+        let bytes = text.as_bytes();
+        let mut buf = [0; CAP];
+
+        let mut ix = 0;
+        while ix < bytes.len() && ix < CAP {
+            buf[ix] = bytes[ix];
+            ix += 1;
+        }
+
+        Message { buf, len: ix }
+    }
+
+    pub fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.buf[..self.len]).unwrap_or_default()
+    }
+
+    /// Char at byte offset `ix`, if any.
+    pub fn char_at(&self, ix: usize) -> Option<char> {
+        self.as_str().get(ix..).and_then(|t| t.chars().next())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == CAP
+    }
+
+    /// Returns `false` when full.
+    pub fn push(&mut self, b: u8) -> bool {
+        if self.is_full() {
+            return false;
+        }
+
+        self.buf[self.len] = b;
+        self.len += 1;
+        true
+    }
+
+    /// Removes last `char`, not only byte.
+    pub fn pop(&mut self) {
+        while self.len > 0 {
+            self.len -= 1;
+            if self.buf[self.len] & 0xc0 != 0x80 {
+                break;
+            }
+        }
+    }
+
+    /// Drops trailing incomplete or invalid UTF-8.
+    pub fn validate(&mut self) {
+        if let Err(e) = core::str::from_utf8(&self.buf[..self.len]) {
+            self.len = e.valid_up_to();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+/// Fails once full, what fit stays.
+impl core::fmt::Write for Message {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        match s.bytes().all(|b| self.push(b)) {
+            true => Ok(()),
+            false => Err(core::fmt::Error),
+        }
+    }
+}
+
+/// What full queue drops.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    Oldest,
+    Newest,
+}
+
+impl Overflow {
+    pub fn from_name(name: &str) -> Option<Overflow> {
+        match name {
+            "oldest" => Some(Overflow::Oldest),
+            "newest" => Some(Overflow::Newest),
+            _ => None,
+        }
+    }
+}
+
+pub struct Queue {
+    msgs: [Message; QUEUE_LEN],
+    head: usize,
+    len: usize,
+    dropped: u32,
+}
+
+impl Queue {
+    pub const fn new() -> Self {
+        Queue {
+            msgs: [Message::EMPTY; QUEUE_LEN],
+            head: 0,
+            len: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Returns `false` when message was dropped, `msg` or oldest one.
+    pub fn push(&mut self, msg: Message, overflow: Overflow) -> bool {
+        let full = self.len == QUEUE_LEN;
+        if full {
+            self.dropped = self.dropped.wrapping_add(1);
+
+            if overflow == Overflow::Newest {
+                return false;
+            }
+        }
+
+        let tail = (self.head + self.len) % QUEUE_LEN;
+        self.msgs[tail] = msg;
+
+        if full {
+            self.head = (self.head + 1) % QUEUE_LEN;
+        } else {
+            self.len += 1;
+        }
+        !full
+    }
+
+    /// Messages lost to overflow.
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn pop(&mut self) -> Option<Message> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let msg = self.msgs[self.head];
+        self.head = (self.head + 1) % QUEUE_LEN;
+        self.len -= 1;
+
+        Some(msg)
+    }
+}
+
+/// FNV-1a of `bytes`.
+pub fn hash(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c_9dc5, |h, b| {
+        (h ^ *b as u32).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// How stored messages take turns.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Rotation {
+    /// Slot after current one.
+    RoundRobin,
+    /// Random slot, none repeats before every other one was shown.
+    Shuffle,
+    /// Random slot, ones shown fewer times weigh more. Not by priority, messages carry none.
+    LeastShown,
+}
+
+impl Rotation {
+    pub const ALL: [Rotation; 3] = [
+        Rotation::RoundRobin,
+        Rotation::Shuffle,
+        Rotation::LeastShown,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Rotation> {
+        match name {
+            "roundrobin" => Some(Rotation::RoundRobin),
+            "shuffle" => Some(Rotation::Shuffle),
+            "leastshown" => Some(Rotation::LeastShown),
+            _ => None,
+        }
+    }
+}
+
+/// Display accounting of stored message.
+#[derive(Clone, Copy)]
+pub struct Airtime {
+    /// Times it was switched to.
+    pub shows: u32,
+    /// RTC0 ticks its frames were up.
+    pub ticks: u32,
+}
+
+/// Slot 0 holds `DEFAULT`, received messages rotate through remaining slots.
+pub struct Store {
+    msgs: [Message; SLOTS],
+    airtime: [Airtime; SLOTS],
+    len: usize,
+    next: usize,
+    /// Slots shown in current shuffle round, bit each.
+    dealt: u32,
+    /// xorshift state, never 0.
+    state: u32,
+}
+
+const _: () = assert!(SLOTS <= 32, "shuffle round takes bit per slot");
+/// Shows behind most shown slot counting into weight, at most that many times as likely.
+const MAX_LAG: u32 = 8;
+
+impl Store {
+    pub const fn new() -> Self {
+        let mut msgs = [Message::EMPTY; SLOTS];
+        msgs[0] = DEFAULT;
+
+        Store {
+            msgs,
+            airtime: [Airtime { shows: 0, ticks: 0 }; SLOTS],
+            len: 1,
+            next: 1,
+            dealt: 0,
+            state: 1,
+        }
+    }
+
+    /// `seed` should differ among boards, device id does.
+    pub fn seed(&mut self, seed: u32) {
+        self.state = seed | 1;
+    }
+
+    /// Returns slot used, its accounting starts over.
+    pub fn add(&mut self, msg: Message) -> usize {
+        let ix = self.next;
+        self.msgs[ix] = msg;
+        self.airtime[ix] = Airtime { shows: 0, ticks: 0 };
+
+        self.len = self.len.max(ix + 1);
+        self.next = if ix + 1 == SLOTS { 1 } else { ix + 1 };
+
+        ix
+    }
+
+    pub fn get(&self, ix: usize) -> &Message {
+        &self.msgs[ix % self.len]
+    }
+
+    pub fn next(&self, ix: usize) -> usize {
+        (ix + 1) % self.len
+    }
+
+    /// Slot to show after `ix`, never `ix` itself while there are others.
+    pub fn rotate(&mut self, ix: usize, rotation: Rotation) -> usize {
+        let ix = ix % self.len;
+        if self.len == 1 {
+            return ix;
+        }
+
+        let mut pick = self.random();
+        match rotation {
+            Rotation::RoundRobin => self.next(ix),
+            Rotation::Shuffle => {
+                let all = (1 << self.len) - 1;
+                self.dealt |= 1 << ix;
+                if self.dealt & all == all {
+                    self.dealt = 1 << ix;
+                }
+
+                let left = (self.dealt ^ all).count_ones();
+                let next = (0..self.len)
+                    .filter(|s| self.dealt & 1 << s == 0)
+                    .nth((pick % left) as usize)
+                    .unwrap_or(ix);
+                self.dealt |= 1 << next;
+                next
+            }
+            Rotation::LeastShown => {
+                let most = self.airtime.iter().map(|a| a.shows).max().unwrap_or(0);
+                let weight = |s: usize| match s == ix {
+                    true => 0,
+                    false => 1 + most.saturating_sub(self.airtime[s].shows).min(MAX_LAG),
+                };
+
+                pick %= (0..self.len).map(weight).sum::<u32>();
+                (0..self.len)
+                    .find(|s| match pick.checked_sub(weight(*s)) {
+                        Some(rest) => {
+                            pick = rest;
+                            false
+                        }
+                        None => true,
+                    })
+                    .unwrap_or(ix)
+            }
+        }
+    }
+
+    fn random(&mut self) -> u32 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.state = x;
+        x
+    }
+
+    /// Slot shown least so far.
+    pub fn least_aired(&self) -> usize {
+        (0..self.len)
+            .min_by_key(|ix| self.airtime[*ix].ticks)
+            .unwrap_or(0)
+    }
+
+    pub fn count_show(&mut self, ix: usize) {
+        let a = &mut self.airtime[ix % self.len];
+        a.shows = a.shows.saturating_add(1);
+    }
+
+    pub fn count_ticks(&mut self, ix: usize, ticks: u32) {
+        let a = &mut self.airtime[ix % self.len];
+        a.ticks = a.ticks.saturating_add(ticks);
+    }
+
+    /// Slot, message and its accounting.
+    pub fn list(&self) -> impl Iterator<Item = (usize, &Message, Airtime)> + '_ {
+        (0..self.len).map(|ix| (ix, &self.msgs[ix], self.airtime[ix]))
+    }
+}
@@ -0,0 +1,82 @@
+//! Chat mood from keywords in messages, shifting sparkle of text.
+//!
+//! Cheerful words make text twinkle wider, gloomy ones dim it and make it steadier.
+
+#![deny(clippy::indexing_slicing, clippy::unwrap_used)]
+
+use crate::{animation::Sparkle, message::Message, tick};
+
+const POSITIVE: [&str; 10] = [
+    "good", "great", "love", "happy", "yay", "cool", "nice", "thanks", "fun", "win",
+];
+const NEGATIVE: [&str; 10] = [
+    "bad", "sad", "hate", "angry", "ugh", "boring", "tired", "sorry", "lost", "fail",
+];
+
+/// Mood saturates at plus or minus this.
+const MAX: i8 = 4;
+/// Ticks for mood to move one step back to neutral.
+const DECAY: u32 = 30 * tick::PER_SEC;
+
+pub struct Mood {
+    score: i8,
+    since: u32,
+}
+
+impl Mood {
+    pub const fn new() -> Self {
+        Mood { score: 0, since: 0 }
+    }
+
+    /// Scores words of `msg`, any letter case, `now` in RTC0 ticks.
+    pub fn feed(&mut self, msg: &Message, now: u32) {
+        self.decay(now);
+
+        let words = msg
+            .as_str()
+            .split(|c: char| !c.is_ascii_alphabetic())
+            .filter(|w| !w.is_empty());
+
+        for word in words {
+            let hit = |list: &[&str]| list.iter().any(|w| w.eq_ignore_ascii_case(word));
+            if hit(&POSITIVE) {
+                self.score = (self.score + 1).min(MAX);
+            } else if hit(&NEGATIVE) {
+                self.score = (self.score - 1).max(-MAX);
+            }
+        }
+    }
+
+    /// `base` shifted by mood, floor drops when cheerful, ceiling when gloomy.
+    pub fn sparkle(&mut self, base: Sparkle, now: u32) -> Sparkle {
+        self.decay(now);
+
+        let shift = self.score.unsigned_abs();
+        if self.score == 0 {
+            base
+        } else if self.score > 0 {
+            Sparkle {
+                floor: base.floor.saturating_sub(shift).max(1),
+                ceil: 9,
+            }
+        } else {
+            let ceil = base.ceil.saturating_sub(shift).max(1);
+            Sparkle {
+                floor: base.floor.min(ceil),
+                ceil,
+            }
+        }
+    }
+
+    fn decay(&mut self, now: u32) {
+        if self.score == 0 {
+            self.since = now;
+            return;
+        }
+
+        while tick::elapsed(self.since, now) >= DECAY && self.score != 0 {
+            self.since = self.since.wrapping_add(DECAY);
+            self.score -= self.score.signum();
+        }
+    }
+}
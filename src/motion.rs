@@ -0,0 +1,161 @@
+//! LSM303AGR accelerometer on internal I2C bus, shake and tilt gestures.
+
+use crate::tick;
+use microbit::{
+    board::I2CInternalPins,
+    hal::twim::{Frequency, Twim},
+    pac::TWIM0,
+};
+
+const ADDRESS: u8 = 0x19;
+
+const WHO_AM_I_A: u8 = 0x0f;
+const CTRL_REG1_A: u8 = 0x20;
+const CTRL_REG4_A: u8 = 0x23;
+const OUT_X_L_A: u8 = 0x28;
+/// Register address auto increment.
+const AUTO_INC: u8 = 0x80;
+const WHO_AM_I: u8 = 0x33;
+
+/// RTC0 ticks between samples, 20 Hz.
+pub const SAMPLE_TICKS: u32 = tick::PER_SEC / 20;
+
+/// Square of acceleration, in mg, counting towards shake.
+const SHAKE_SQ: i32 = 1800 * 1800;
+/// Strong samples within `SHAKE_WINDOW` making shake.
+const SHAKE_SAMPLES: u8 = 3;
+const SHAKE_WINDOW: u32 = tick::PER_SEC / 2;
+/// Ticks after shake ignoring further ones.
+const SHAKE_COOLDOWN: u32 = tick::PER_SEC;
+
+/// `x` in mg making tilt, and one returning to level.
+const TILT: i16 = 500;
+const LEVEL: i16 = 250;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Gesture {
+    Shake,
+    TiltLeft,
+    TiltRight,
+}
+
+pub struct Accel {
+    twim: Twim<TWIM0>,
+}
+
+impl Accel {
+    /// `None` when sensor does not answer.
+    pub fn new(twim0: TWIM0, pins: I2CInternalPins) -> Option<Self> {
+        let mut twim = Twim::new(twim0, pins.into(), Frequency::K400);
+
+        let mut id = [0];
+        twim.copy_write_then_read(ADDRESS, &[WHO_AM_I_A], &mut id)
+            .ok()?;
+        if id[0] != WHO_AM_I {
+            return None;
+        }
+
+        // EasyDMA reads RAM only, constants could live in flash
+        // 50 Hz, all axes; block data update, high resolution, ±2 g
+        for reg in [[CTRL_REG1_A, 0x47], [CTRL_REG4_A, 0x88]] {
+            twim.write(ADDRESS, &reg).ok()?;
+        }
+
+        Some(Accel { twim })
+    }
+
+    /// `x`, `y`, `z` in mg, blocks for transfer.
+    pub fn sample(&mut self) -> Option<[i16; 3]> {
+        let mut out = [0; 6];
+        self.twim
+            .copy_write_then_read(ADDRESS, &[OUT_X_L_A | AUTO_INC], &mut out)
+            .ok()?;
+
+        // left justified 12 bits, 1 mg per digit
+        let axis = |ix: usize| i16::from_le_bytes([out[ix * 2], out[ix * 2 + 1]]) >> 4;
+        Some([axis(0), axis(1), axis(2)])
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Tilt {
+    Level,
+    Left,
+    Right,
+}
+
+/// Turns samples into gestures.
+pub struct Gestures {
+    strong: u8,
+    first_strong: u32,
+    last_shake: Option<u32>,
+    tilt: Tilt,
+}
+
+impl Gestures {
+    pub const fn new() -> Self {
+        Gestures {
+            strong: 0,
+            first_strong: 0,
+            last_shake: None,
+            tilt: Tilt::Level,
+        }
+    }
+
+    /// `now` in RTC0 ticks.
+    pub fn feed(&mut self, [x, y, z]: [i16; 3], now: u32) -> Option<Gesture> {
+        if self.shake(x, y, z, now) {
+            return Some(Gesture::Shake);
+        }
+
+        let tilt = if x <= -TILT {
+            Tilt::Left
+        } else if x >= TILT {
+            Tilt::Right
+        } else if x.abs() <= LEVEL {
+            Tilt::Level
+        } else {
+            self.tilt
+        };
+
+        if tilt == self.tilt {
+            return None;
+        }
+
+        self.tilt = tilt;
+        match tilt {
+            Tilt::Left => Some(Gesture::TiltLeft),
+            Tilt::Right => Some(Gesture::TiltRight),
+            Tilt::Level => None,
+        }
+    }
+
+    fn shake(&mut self, x: i16, y: i16, z: i16, now: u32) -> bool {
+        let sq = [x, y, z].iter().map(|a| *a as i32 * *a as i32).sum::<i32>();
+
+        if let Some(last) = self.last_shake {
+            if tick::elapsed(last, now) < SHAKE_COOLDOWN {
+                return false;
+            }
+            self.last_shake = None;
+        }
+
+        if sq < SHAKE_SQ {
+            return false;
+        }
+
+        if self.strong == 0 || tick::elapsed(self.first_strong, now) > SHAKE_WINDOW {
+            self.strong = 0;
+            self.first_strong = now;
+        }
+
+        self.strong += 1;
+        if self.strong < SHAKE_SAMPLES {
+            return false;
+        }
+
+        self.strong = 0;
+        self.last_shake = Some(now);
+        true
+    }
+}
@@ -0,0 +1,69 @@
+//! Axis swap and mirroring for matrices wired rotated, applied to every frame last.
+
+#![deny(clippy::indexing_slicing, clippy::unwrap_used)]
+
+use crate::memory::Lattice;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Orient {
+    /// Rows become columns, applied before mirroring.
+    pub swap: bool,
+    /// Columns right to left.
+    pub mirror_x: bool,
+    /// Rows bottom to top.
+    pub mirror_y: bool,
+}
+
+impl Orient {
+    pub const NONE: Orient = Orient {
+        swap: false,
+        mirror_x: false,
+        mirror_y: false,
+    };
+
+    /// `none`, or any of `s`, `x` and `y` for swap and mirrors.
+    pub fn from_name(name: &str) -> Option<Orient> {
+        let mut orient = Orient::NONE;
+        if name == "none" {
+            return Some(orient);
+        }
+
+        for c in name.chars() {
+            let flag = match c {
+                's' => &mut orient.swap,
+                'x' => &mut orient.mirror_x,
+                'y' => &mut orient.mirror_y,
+                _ => return None,
+            };
+            *flag = true;
+        }
+        (!name.is_empty()).then_some(orient)
+    }
+
+    /// Low 3 bits.
+    pub fn bits(self) -> u8 {
+        self.swap as u8 | (self.mirror_x as u8) << 1 | (self.mirror_y as u8) << 2
+    }
+
+    pub fn from_bits(bits: u8) -> Orient {
+        Orient {
+            swap: bits & 1 != 0,
+            mirror_x: bits & 2 != 0,
+            mirror_y: bits & 4 != 0,
+        }
+    }
+
+    pub fn apply(self, latt: &Lattice) -> Lattice {
+        let mut out = [[0; 5]; 5];
+
+        for (rix, row) in out.iter_mut().enumerate() {
+            for (cix, px) in row.iter_mut().enumerate() {
+                let r = if self.mirror_y { 4 - rix } else { rix };
+                let c = if self.mirror_x { 4 - cix } else { cix };
+                let (r, c) = if self.swap { (c, r) } else { (r, c) };
+                *px = latt.get(r).and_then(|row| row.get(c)).copied().unwrap_or(0);
+            }
+        }
+        out
+    }
+}
@@ -0,0 +1,105 @@
+//! Test patterns for LED QA, brightness calibration and showing greyscale levels.
+
+#![deny(clippy::indexing_slicing, clippy::unwrap_used)]
+
+use crate::memory::Lattice;
+
+/// Frames each pattern is shown when cycling.
+const AUTO_FRAMES: u32 = 25;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Pattern {
+    /// Every pixel full.
+    On,
+    Checker,
+    /// Checker with other pixels lit.
+    Inverse,
+    /// Levels rising from top left to bottom right corner.
+    Gradient,
+    /// Single lit row moving down.
+    Rows,
+    /// Single lit column moving right.
+    Cols,
+}
+
+impl Pattern {
+    pub const ALL: [Pattern; 6] = [
+        Pattern::On,
+        Pattern::Checker,
+        Pattern::Inverse,
+        Pattern::Gradient,
+        Pattern::Rows,
+        Pattern::Cols,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Pattern::On => "on",
+            Pattern::Checker => "checker",
+            Pattern::Inverse => "inverse",
+            Pattern::Gradient => "gradient",
+            Pattern::Rows => "rows",
+            Pattern::Cols => "cols",
+        }
+    }
+
+    /// `frame` moves sweeps.
+    pub fn lattice(self, frame: u32) -> Lattice {
+        let step = (frame % 5) as usize;
+        let mut latt = [[0; 5]; 5];
+
+        for (rix, row) in latt.iter_mut().enumerate() {
+            for (cix, px) in row.iter_mut().enumerate() {
+                let lit = match self {
+                    Pattern::On => true,
+                    Pattern::Checker => (rix + cix) % 2 == 0,
+                    Pattern::Inverse => (rix + cix) % 2 == 1,
+                    Pattern::Gradient => {
+                        // 9 levels over 9 diagonals
+                        *px = (rix + cix + 1) as u8;
+                        continue;
+                    }
+                    Pattern::Rows => rix == step,
+                    Pattern::Cols => cix == step,
+                };
+                *px = if lit { 9 } else { 0 };
+            }
+        }
+        latt
+    }
+}
+
+/// Pattern shown instead of everything else.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Test {
+    Off,
+    Fixed(Pattern),
+    /// All patterns in turn.
+    Auto,
+}
+
+impl Test {
+    /// `off`, `auto` or pattern name.
+    pub fn from_name(name: &str) -> Option<Test> {
+        match name {
+            "off" => Some(Test::Off),
+            "auto" => Some(Test::Auto),
+            _ => Pattern::ALL
+                .into_iter()
+                .find(|p| p.name() == name)
+                .map(Test::Fixed),
+        }
+    }
+
+    pub fn lattice(self, frame: u32) -> Option<Lattice> {
+        let pattern = match self {
+            Test::Off => return None,
+            Test::Fixed(p) => p,
+            Test::Auto => {
+                let ix = (frame / AUTO_FRAMES) as usize % Pattern::ALL.len();
+                *Pattern::ALL.get(ix)?
+            }
+        };
+        Some(pattern.lattice(frame))
+    }
+}
@@ -0,0 +1,90 @@
+//! Radio boards heard lately, by beacon, answer or text, for `/status`.
+
+#![deny(clippy::indexing_slicing, clippy::unwrap_used)]
+
+use crate::tick;
+
+/// Boards remembered, one heard longest ago gives way.
+const NODES: usize = 16;
+/// Board not heard this long is gone, 6 beacons missed.
+const WINDOW: u32 = 30 * tick::PER_SEC;
+
+pub struct Peers {
+    /// Node and RTC0 tick it was last heard at.
+    heard: [Option<(u32, u32)>; NODES],
+}
+
+impl Peers {
+    pub const fn new() -> Self {
+        Peers {
+            heard: [None; NODES],
+        }
+    }
+
+    pub fn heard(&mut self, node: u32, now: u32) {
+        let ix = self
+            .heard
+            .iter()
+            .position(|h| h.is_some_and(|(n, _)| n == node))
+            .or_else(|| self.heard.iter().position(Option::is_none))
+            .or_else(|| {
+                let age = |h: &Option<(u32, u32)>| h.map_or(0, |(_, at)| tick::elapsed(at, now));
+                let stalest = self.heard.iter().enumerate().max_by_key(|(_, h)| age(h));
+                stalest.map(|(ix, _)| ix)
+            });
+
+        if let Some(slot) = ix.and_then(|ix| self.heard.get_mut(ix)) {
+            *slot = Some((node, now));
+        }
+    }
+
+    /// Boards heard within `WINDOW` before `now`.
+    pub fn count(&self, now: u32) -> usize {
+        self.heard
+            .iter()
+            .flatten()
+            .filter(|(_, at)| tick::elapsed(*at, now) < WINDOW)
+            .count()
+    }
+}
+
+impl Default for Peers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distinct() {
+        let mut peers = Peers::new();
+        peers.heard(7, 0);
+        peers.heard(7, 10);
+        peers.heard(9, 20);
+        assert_eq!(peers.count(20), 2);
+    }
+
+    #[test]
+    fn forgets_silent() {
+        let mut peers = Peers::new();
+        peers.heard(7, 0);
+        peers.heard(9, 100);
+        assert_eq!(peers.count(WINDOW - 1), 2);
+        assert_eq!(peers.count(WINDOW), 1);
+        assert_eq!(peers.count(100 + WINDOW), 0);
+    }
+
+    #[test]
+    fn full_replaces_stalest() {
+        let mut peers = Peers::new();
+        for node in 0..NODES as u32 {
+            peers.heard(node, node + 1);
+        }
+        peers.heard(100, 50);
+        assert_eq!(peers.count(50), NODES);
+        assert!(!peers.heard.iter().flatten().any(|(n, _)| *n == 0));
+    }
+}
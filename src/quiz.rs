@@ -0,0 +1,226 @@
+//! Classroom voting over radio, teacher board asks and tallies, student boards answer A or B.
+//!
+//! Teacher repeats open question every `REPEAT_TICKS`, students repeat their answer on hearing it,
+//! so lost frames get replaced without acknowledgements. Votes are counted per node, last one wins.
+//! Closing is repeated few times too, and student not hearing question for a while leaves it.
+
+#![deny(clippy::indexing_slicing, clippy::unwrap_used)]
+
+use crate::{font, memory::Lattice, tick};
+
+/// RTC0 ticks between question repeats.
+pub const REPEAT_TICKS: u32 = 2 * tick::PER_SEC;
+/// Voters teacher keeps track of.
+const VOTERS: usize = 32;
+/// Close frames repeated after first one.
+const CLOSE_REPEATS: u8 = 3;
+/// Student not hearing question this long leaves it, few repeats lost.
+const STUDENT_TIMEOUT: u32 = 5 * REPEAT_TICKS;
+
+pub const A: u8 = 0;
+pub const B: u8 = 1;
+
+enum Role {
+    Off,
+    Teacher(u8),
+    Student {
+        id: u8,
+        answer: Option<u8>,
+        /// Tick question was last heard.
+        heard: u32,
+    },
+}
+
+pub struct Quiz {
+    role: Role,
+    /// Node and its choice, teacher only.
+    votes: [(u32, u8); VOTERS],
+    len: usize,
+    node: u32,
+    /// Close frames left to repeat, teacher only.
+    closing: u8,
+}
+
+impl Quiz {
+    pub const fn new() -> Self {
+        Quiz {
+            role: Role::Off,
+            votes: [(0, 0); VOTERS],
+            len: 0,
+            node: 0,
+            closing: 0,
+        }
+    }
+
+    /// Answers are sent as from `node`.
+    pub fn set_node(&mut self, node: u32) {
+        self.node = node;
+    }
+
+    pub fn node(&self) -> u32 {
+        self.node
+    }
+
+    pub fn is_on(&self) -> bool {
+        !matches!(self.role, Role::Off)
+    }
+
+    /// Opens question `id`, not 0, as teacher.
+    pub fn ask(&mut self, id: u8) {
+        self.role = Role::Teacher(id);
+        self.len = 0;
+        self.closing = 0;
+    }
+
+    /// Teacher has question or close to repeat.
+    pub fn repeats(&self) -> bool {
+        matches!(self.role, Role::Teacher(_)) || self.closing > 0
+    }
+
+    /// Question to repeat, 0 while closing, teacher only.
+    pub fn repeat(&mut self) -> Option<u8> {
+        match self.role {
+            Role::Teacher(id) => Some(id),
+            _ if self.closing > 0 => {
+                self.closing -= 1;
+                Some(0)
+            }
+            _ => None,
+        }
+    }
+
+    /// Closes question, returns votes for A and B when teacher.
+    pub fn close(&mut self) -> Option<(usize, usize)> {
+        let tally = self.tally();
+        if tally.is_some() {
+            self.closing = CLOSE_REPEATS;
+        }
+        self.role = Role::Off;
+        tally
+    }
+
+    /// Student leaves question not heard within `STUDENT_TIMEOUT`, `now` in RTC0 ticks.
+    pub fn poll(&mut self, now: u32) {
+        if let Role::Student { heard, .. } = self.role {
+            if tick::elapsed(heard, now) > STUDENT_TIMEOUT {
+                self.role = Role::Off;
+            }
+        }
+    }
+
+    pub fn tally(&self) -> Option<(usize, usize)> {
+        let Role::Teacher(_) = self.role else {
+            return None;
+        };
+
+        let a = self
+            .votes
+            .iter()
+            .take(self.len)
+            .filter(|(_, c)| *c == A)
+            .count();
+        Some((a, self.len - a))
+    }
+
+    /// Question heard at `now`, returns answer to repeat.
+    pub fn question(&mut self, id: u8, now: u32) -> Option<(u8, u8)> {
+        match &mut self.role {
+            Role::Teacher(_) => None,
+            _ if id == 0 => {
+                self.role = Role::Off;
+                None
+            }
+            Role::Student {
+                id: open,
+                answer,
+                heard,
+            } if *open == id => {
+                *heard = now;
+                answer.map(|c| (id, c))
+            }
+            _ => {
+                self.role = Role::Student {
+                    id,
+                    answer: None,
+                    heard: now,
+                };
+                None
+            }
+        }
+    }
+
+    /// Student picks `choice`, returns answer to send.
+    pub fn answer(&mut self, choice: u8) -> Option<(u8, u8)> {
+        match &mut self.role {
+            Role::Student { id, answer, .. } => {
+                *answer = Some(choice);
+                Some((*id, choice))
+            }
+            _ => None,
+        }
+    }
+
+    /// Answer heard, counted when for open question.
+    pub fn vote(&mut self, for_id: u8, choice: u8, node: u32) {
+        let Role::Teacher(id) = self.role else {
+            return;
+        };
+
+        if for_id != id || choice > B {
+            return;
+        }
+
+        let (votes, free) = self.votes.split_at_mut(self.len.min(VOTERS));
+
+        if let Some(v) = votes.iter_mut().find(|(n, _)| *n == node) {
+            v.1 = choice;
+        } else if let Some(v) = free.first_mut() {
+            *v = (node, choice);
+            self.len += 1;
+        }
+    }
+
+    /// Teacher shows bar for A on left and for B on right, student `?` or its answer.
+    pub fn lattice(&self) -> Option<Lattice> {
+        match &self.role {
+            Role::Off => None,
+            Role::Teacher(_) => {
+                let (a, b) = self.tally()?;
+                let max = a.max(b).max(1);
+                let height = |n: usize| (n * 5).div_ceil(max);
+
+                let mut latt = [[0; 5]; 5];
+                for (col, h) in [(0, height(a)), (3, height(b))] {
+                    for row in latt.iter_mut().rev().take(h) {
+                        row.iter_mut().skip(col).take(2).for_each(|px| *px = 9);
+                    }
+                }
+                Some(latt)
+            }
+            Role::Student { answer, .. } => {
+                let c = match answer {
+                    None => '?',
+                    Some(A) => 'A',
+                    Some(_) => 'B',
+                };
+                Some(glyph(c))
+            }
+        }
+    }
+}
+
+/// `c` centered at full brightness.
+fn glyph(c: char) -> Lattice {
+    let def = font::col_def(c);
+    let off = (5 - def.len().min(5)) / 2;
+
+    let mut latt = [[0; 5]; 5];
+    for (cix, col) in def.iter().take(5).enumerate() {
+        for (rix, row) in latt.iter_mut().enumerate() {
+            if let Some(px) = row.get_mut(off + cix).filter(|_| col & (1 << rix) != 0) {
+                *px = 9;
+            }
+        }
+    }
+    latt
+}
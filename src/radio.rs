@@ -0,0 +1,407 @@
+//! RADIO broadcast between boards, framed like micro:bit DAL radio (MakeCode `radio` blocks).
+//!
+//! On air frame is `[len][version][group][protocol][payload]`, 1 Mbit, channel 7, 16-bit CRC.
+//! Messages travel as fragments `[KIND][seq][ix | LAST][sum][chunk]` with wrapping byte sum of
+//! `seq`, `ix` and `chunk`. Canvas pixels travel as `[PIXEL][ix][on][clock LE][node LE][sum]`,
+//! quiz as `[QUESTION][id][sum]` and `[ANSWER][id][choice][node LE][sum]`, presence beacons as
+//! `[BEACON][node LE][sum]`, sum leaves out kind.
+//! MakeCode string packets are shown too.
+//!
+//! Anything heard on air is untrusted, module must not panic.
+
+#![deny(clippy::indexing_slicing, clippy::unwrap_used)]
+
+use crate::{
+    airtime,
+    canvas::{Stamp, Update},
+    errors::{self, Fault},
+    message::{Message, CAP},
+};
+use microbit::pac::RADIO;
+
+/// Frames `broadcast` sends for `msg`.
+pub fn fragments(msg: &Message) -> u32 {
+    msg.as_str().len().div_ceil(CHUNK) as u32
+}
+
+/// Frame payload after `len`, DAL `MICROBIT_RADIO_MAX_PACKET_SIZE`.
+const MAX_LEN: usize = 32;
+const FRAME_LEN: usize = MAX_LEN + 1;
+const HEADER_LEN: usize = 4;
+
+const VERSION: u8 = 1;
+const PROTOCOL_DATAGRAM: u8 = 1;
+pub const GROUP: u8 = 0;
+
+/// First datagram byte of fragments, MakeCode packet types are small.
+const KIND: u8 = 0x80;
+const LAST: u8 = 0x80;
+const FRAG_HEADER_LEN: usize = 4;
+const CHUNK: usize = MAX_LEN + 1 - HEADER_LEN - FRAG_HEADER_LEN;
+
+const PIXEL: u8 = 0x81;
+const PIXEL_LEN: usize = 12;
+const QUESTION: u8 = 0x82;
+const QUESTION_LEN: usize = 3;
+const ANSWER: u8 = 0x83;
+const ANSWER_LEN: usize = 8;
+const BEACON: u8 = 0x84;
+const BEACON_LEN: usize = 6;
+
+/// MakeCode `radio.sendString`, `[2][time LE][serial LE][len][text]`.
+const MAKECODE_STRING: u8 = 2;
+const MAKECODE_SERIAL_IX: usize = 5;
+const MAKECODE_LEN_IX: usize = 9;
+
+/// Whole message and few pixel updates.
+const TXQ_LEN: usize = CAP.div_ceil(CHUNK) + 4;
+const _: () = assert!(
+    CAP.div_ceil(CHUNK) as u32 <= airtime::BURST,
+    "longest message must fit airtime burst"
+);
+
+type Frame = [u8; FRAME_LEN];
+
+pub enum Received {
+    /// Text and MakeCode sender serial, own fragments carry none.
+    Text(Message, Option<u32>),
+    Pixel(Update),
+    /// Question open, 0 closes it.
+    Question(u8),
+    Answer {
+        id: u8,
+        choice: u8,
+        node: u32,
+    },
+    Beacon(u32),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Rx,
+    Tx,
+    /// Powered down, frames queue up until `wake`.
+    Off,
+}
+
+pub struct Radio {
+    radio: RADIO,
+    /// DMA buffer, `Radio` must not move while receiving.
+    buf: Frame,
+    state: State,
+    txq: [Frame; TXQ_LEN],
+    head: usize,
+    len: usize,
+    seq: u8,
+    asm: Reassembly,
+}
+
+impl Radio {
+    /// HFXO must run. Reception starts with `listen` once in place.
+    pub fn new(radio: RADIO) -> Self {
+        unsafe {
+            radio.mode.write(|w| w.mode().nrf_1mbit());
+            radio.txpower.write(|w| w.txpower()._0d_bm());
+            radio.frequency.write(|w| w.frequency().bits(7));
+            // LFLEN 8 bits, no S0 and S1
+            radio.pcnf0.write(|w| w.lflen().bits(8));
+            radio.pcnf1.write(|w| {
+                w.maxlen()
+                    .bits(MAX_LEN as u8)
+                    .balen()
+                    .bits(4)
+                    .whiteen()
+                    .enabled()
+            });
+            radio.base0.write(|w| w.bits(0x7562_6974));
+            radio.prefix0.write(|w| w.ap0().bits(GROUP));
+            radio.txaddress.write(|w| w.txaddress().bits(0));
+            radio.rxaddresses.write(|w| w.addr0().enabled());
+            radio.crccnf.write(|w| w.len().two());
+            radio.crcinit.write(|w| w.crcinit().bits(0xffff));
+            radio.crcpoly.write(|w| w.crcpoly().bits(0x11021));
+            radio.datawhiteiv.write(|w| w.datawhiteiv().bits(0x18));
+        }
+
+        radio
+            .shorts
+            .write(|w| w.ready_start().enabled().end_disable().enabled());
+        radio.intenset.write(|w| w.disabled().set());
+
+        Radio {
+            radio,
+            buf: [0; FRAME_LEN],
+            state: State::Rx,
+            txq: [[0; FRAME_LEN]; TXQ_LEN],
+            head: 0,
+            len: 0,
+            seq: 0,
+            asm: Reassembly::new(),
+        }
+    }
+
+    pub fn listen(&mut self) {
+        self.state = State::Rx;
+        self.start(|r| r.tasks_rxen.write(|w| unsafe { w.bits(1) }));
+    }
+
+    /// Stops receiving or sending, frame on air is lost. Returns once radio is disabled,
+    /// HFXO can be stopped then.
+    pub fn sleep(&mut self) {
+        self.state = State::Off;
+        self.radio.tasks_disable.write(|w| unsafe { w.bits(1) });
+        while !self.radio.state.read().state().is_disabled() {}
+    }
+
+    /// HFXO must run again. Sends what queued meanwhile, or listens.
+    pub fn wake(&mut self) {
+        if self.state == State::Off {
+            self.next();
+        }
+    }
+
+    /// Queues `msg` fragments, returns `false` when they do not fit.
+    pub fn broadcast(&mut self, msg: &Message) -> bool {
+        let bytes = msg.as_str().as_bytes();
+        if fragments(msg) as usize > TXQ_LEN - self.len {
+            return false;
+        }
+
+        self.seq = self.seq.wrapping_add(1);
+
+        let mut datagram = [0; FRAG_HEADER_LEN + CHUNK];
+        let mut chunks = bytes.chunks(CHUNK).enumerate().peekable();
+        while let Some((ix, chunk)) = chunks.next() {
+            let ix = ix as u8 | if chunks.peek().is_none() { LAST } else { 0 };
+            let sum = checksum(self.seq.wrapping_add(ix), chunk);
+
+            datagram[..FRAG_HEADER_LEN].copy_from_slice(&[KIND, self.seq, ix, sum]);
+            copy(&mut datagram[FRAG_HEADER_LEN..], chunk);
+            if let Some(datagram) = datagram.get(..FRAG_HEADER_LEN + chunk.len()) {
+                self.push(datagram);
+            }
+        }
+
+        self.kick();
+        true
+    }
+
+    /// Queues canvas update, returns `false` when queue is full.
+    pub fn send_pixel(&mut self, update: &Update) -> bool {
+        let mut datagram = [0; PIXEL_LEN];
+        datagram[..3].copy_from_slice(&[PIXEL, update.ix, update.on as u8]);
+        datagram[3..7].copy_from_slice(&update.stamp.clock.to_le_bytes());
+        datagram[7..11].copy_from_slice(&update.stamp.node.to_le_bytes());
+
+        self.send_summed(&mut datagram)
+    }
+
+    pub fn send_question(&mut self, id: u8) -> bool {
+        self.send_summed(&mut [QUESTION, id, 0])
+    }
+
+    pub fn send_answer(&mut self, id: u8, choice: u8, node: u32) -> bool {
+        let mut datagram = [0; ANSWER_LEN];
+        datagram[..3].copy_from_slice(&[ANSWER, id, choice]);
+        datagram[3..7].copy_from_slice(&node.to_le_bytes());
+
+        self.send_summed(&mut datagram)
+    }
+
+    pub fn send_beacon(&mut self, node: u32) -> bool {
+        let mut datagram = [0; BEACON_LEN];
+        datagram[0] = BEACON;
+        datagram[1..5].copy_from_slice(&node.to_le_bytes());
+
+        self.send_summed(&mut datagram)
+    }
+
+    /// Fills last byte with sum of all but first and last one.
+    fn send_summed(&mut self, datagram: &mut [u8]) -> bool {
+        if self.len == TXQ_LEN {
+            return false;
+        }
+
+        if let [_, body @ .., sum] = datagram {
+            *sum = checksum(0, body);
+        }
+
+        self.push(datagram);
+        self.kick();
+        true
+    }
+
+    fn push(&mut self, datagram: &[u8]) {
+        let Some(frame) = self.txq.get_mut((self.head + self.len) % TXQ_LEN) else {
+            return;
+        };
+        frame[0] = (HEADER_LEN - 1 + datagram.len().min(FRAME_LEN - HEADER_LEN)) as u8;
+        frame[1..HEADER_LEN].copy_from_slice(&[VERSION, GROUP, PROTOCOL_DATAGRAM]);
+        copy(&mut frame[HEADER_LEN..], datagram);
+
+        self.len += 1;
+    }
+
+    /// DISABLED event hands over to transmission.
+    fn kick(&mut self) {
+        if self.state == State::Rx {
+            self.radio.tasks_disable.write(|w| unsafe { w.bits(1) });
+        }
+    }
+
+    /// Handles DISABLED event, returns what received frame completed.
+    pub fn handle(&mut self) -> Option<Received> {
+        self.radio.events_disabled.reset();
+        if self.state == State::Off {
+            return None;
+        }
+
+        let mut received = None;
+        if self.state == State::Rx && self.radio.events_end.read().bits() != 0 {
+            self.radio.events_end.reset();
+
+            if self.radio.crcstatus.read().crcstatus().is_crcok() {
+                received = self.receive();
+            } else {
+                errors::record(Fault::RadioCrc);
+            }
+        }
+
+        self.next();
+        received
+    }
+
+    /// Sends next queued frame, listens when there is none.
+    fn next(&mut self) {
+        if let Some(frame) = self.txq.get(self.head).filter(|_| self.len > 0) {
+            self.buf = *frame;
+            self.head = (self.head + 1) % TXQ_LEN;
+            self.len -= 1;
+
+            self.state = State::Tx;
+            self.start(|r| r.tasks_txen.write(|w| unsafe { w.bits(1) }));
+        } else {
+            self.listen();
+        }
+    }
+
+    fn start(&mut self, task: impl FnOnce(&RADIO)) {
+        self.radio.events_end.reset();
+        self.radio
+            .packetptr
+            .write(|w| unsafe { w.bits(self.buf.as_ptr() as u32) });
+        task(&self.radio);
+    }
+
+    fn receive(&mut self) -> Option<Received> {
+        let [len, version, group, protocol, rest @ ..] = &self.buf;
+        let len = (*len as usize).min(MAX_LEN);
+        if len < HEADER_LEN || *version != VERSION || *group != GROUP {
+            return None;
+        }
+        if *protocol != PROTOCOL_DATAGRAM {
+            return None;
+        }
+
+        let data = rest.get(..=len - HEADER_LEN)?;
+        let summed = match data {
+            [_, body @ .., sum] => checksum(0, body) == *sum,
+            _ => false,
+        };
+        let word = |ix: usize| {
+            let bytes = data.get(ix..ix + 4)?;
+            Some(u32::from_le_bytes(bytes.try_into().ok()?))
+        };
+
+        match data {
+            [KIND, seq, ix, sum, chunk @ ..] => (checksum(seq.wrapping_add(*ix), chunk) == *sum)
+                .then(|| self.asm.feed(*seq, *ix, chunk))
+                .flatten()
+                .map(|msg| Received::Text(msg, None)),
+            [PIXEL, ix, on, ..] if data.len() == PIXEL_LEN && summed => {
+                Some(Received::Pixel(Update {
+                    ix: *ix,
+                    on: *on != 0,
+                    stamp: Stamp {
+                        clock: word(3)?,
+                        node: word(7)?,
+                    },
+                }))
+            }
+            [BEACON, ..] if data.len() == BEACON_LEN && summed => Some(Received::Beacon(word(1)?)),
+            [QUESTION, id, ..] if data.len() == QUESTION_LEN && summed => {
+                Some(Received::Question(*id))
+            }
+            [ANSWER, id, choice, ..] if data.len() == ANSWER_LEN && summed => {
+                Some(Received::Answer {
+                    id: *id,
+                    choice: *choice,
+                    node: word(3)?,
+                })
+            }
+            [MAKECODE_STRING, ..] => {
+                let (len, text) = data.get(MAKECODE_LEN_IX..)?.split_first()?;
+                let text = text.get(..(*len as usize).min(text.len()))?;
+
+                let mut msg = Message::EMPTY;
+                text.iter().for_each(|b| _ = msg.push(*b));
+                msg.validate();
+                (!msg.is_empty()).then_some(Received::Text(msg, word(MAKECODE_SERIAL_IX)))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Copies what fits of `src`.
+fn copy(dst: &mut [u8], src: &[u8]) {
+    dst.iter_mut().zip(src).for_each(|(d, s)| *d = *s);
+}
+
+/// Wrapping byte sum.
+fn checksum(init: u8, bytes: &[u8]) -> u8 {
+    bytes.iter().fold(init, |s, b| s.wrapping_add(*b))
+}
+
+/// Joins fragments of one `seq` arriving in order, anything else restarts it.
+struct Reassembly {
+    msg: Message,
+    seq: u8,
+    next: u8,
+}
+
+impl Reassembly {
+    const fn new() -> Self {
+        Reassembly {
+            msg: Message::EMPTY,
+            seq: 0,
+            next: 0,
+        }
+    }
+
+    fn feed(&mut self, seq: u8, ix: u8, chunk: &[u8]) -> Option<Message> {
+        let last = ix & LAST == LAST;
+        let ix = ix & !LAST;
+
+        if ix == 0 {
+            self.msg.clear();
+            self.seq = seq;
+        } else if seq != self.seq || ix != self.next {
+            self.next = 0;
+            return None;
+        }
+
+        for b in chunk {
+            _ = self.msg.push(*b);
+        }
+        self.next = ix + 1;
+
+        if !last {
+            return None;
+        }
+
+        self.next = 0;
+        self.msg.validate();
+        (!self.msg.is_empty()).then_some(self.msg)
+    }
+}
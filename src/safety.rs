@@ -0,0 +1,39 @@
+//! Photosensitive safety limiting how fast any pixel may change brightness.
+//!
+//! Full swing takes at least `SWING_TICKS`, so whole matrix flashes stay below 3 per second.
+
+#![deny(clippy::indexing_slicing, clippy::unwrap_used)]
+
+use crate::{memory::Lattice, tick};
+
+/// RTC0 ticks of full 0 to 9 swing.
+const SWING_TICKS: u32 = tick::PER_SEC / 3;
+
+pub struct Limiter {
+    shown: Lattice,
+    at: u32,
+}
+
+impl Limiter {
+    pub const fn new() -> Self {
+        Limiter {
+            shown: [[0; 5]; 5],
+            at: 0,
+        }
+    }
+
+    /// Moves shown frame towards `latt`, `now` in RTC0 ticks.
+    pub fn apply(&mut self, latt: &Lattice, now: u32) -> Lattice {
+        let step = (tick::elapsed(self.at, now) * 9 / SWING_TICKS).clamp(1, 9) as u8;
+        self.at = now;
+
+        for (shown, target) in self.shown.iter_mut().flatten().zip(latt.iter().flatten()) {
+            *shown = if *target > *shown {
+                (*shown + step).min(*target)
+            } else {
+                shown.saturating_sub(step).max(*target)
+            };
+        }
+        self.shown
+    }
+}
@@ -0,0 +1,277 @@
+//! Scrolling message through 5×5 lattice, horizontally column by column or vertically row by row.
+
+#![deny(
+    clippy::indexing_slicing,
+    clippy::unwrap_used,
+    clippy::arithmetic_side_effects
+)]
+
+use crate::{
+    animation::{Animation, Sparkle},
+    font,
+    memory::Lattice,
+    message::Message,
+};
+use microbit::hal::Rng;
+
+/// Spacing columns or rows after whole message.
+const FINAL_SP: u8 = 5;
+
+/// Walks text glyph by glyph, `FINAL_SP` spacing after last one and 1 between others.
+/// Backwards it starts with last glyph and its last step.
+struct Cursor {
+    /// Byte offset of current char, of its end when backwards.
+    char_ix: usize,
+    /// Column or row of current char.
+    step: usize,
+    /// Spacing steps left to insert.
+    ins_sp: u8,
+    back: bool,
+    /// Char last step of which was drawn by last advance.
+    done: Option<char>,
+}
+
+impl Cursor {
+    const fn new() -> Self {
+        Cursor {
+            char_ix: 0,
+            step: 0,
+            ins_sp: 0,
+            back: false,
+            done: None,
+        }
+    }
+
+    fn start(text: &Message, back: bool) -> Self {
+        Cursor {
+            char_ix: if back { text.as_str().len() } else { 0 },
+            back,
+            ..Cursor::new()
+        }
+    }
+
+    fn char(&self, text: &Message) -> Option<char> {
+        if self.back {
+            let head = text.as_str().get(..self.char_ix);
+            head.and_then(|t| t.chars().next_back())
+        } else {
+            text.char_at(self.char_ix)
+        }
+    }
+
+    /// Char to draw now, `None` for spacing.
+    fn current(&self, text: &Message) -> Option<char> {
+        self.char(text).filter(|_| self.ins_sp == 0)
+    }
+
+    /// Column or row of current char to draw, of `steps` per char.
+    fn index(&self, steps: usize) -> usize {
+        if self.back {
+            steps.saturating_sub(self.step).saturating_sub(1)
+        } else {
+            self.step
+        }
+    }
+
+    /// Moves by one step of `steps` per char.
+    fn advance(&mut self, text: &Message, steps: usize) {
+        self.done = None;
+        self.step = self.step.saturating_add(1);
+        if self.step < steps {
+            return;
+        }
+
+        self.step = 0;
+        if self.ins_sp > 0 {
+            self.ins_sp = self.ins_sp.saturating_sub(1);
+            return;
+        }
+
+        let done = self.char(text);
+        self.done = done;
+        let len = done.map_or(0, char::len_utf8);
+        let past = if self.back {
+            self.char_ix = self.char_ix.saturating_sub(len);
+            self.char_ix == 0
+        } else {
+            self.char_ix = self.char_ix.saturating_add(len);
+            self.char_ix >= text.as_str().len()
+        };
+
+        self.ins_sp = if past {
+            *self = Cursor {
+                done,
+                ..Cursor::start(text, self.back)
+            };
+            FINAL_SP
+        } else {
+            1
+        };
+    }
+}
+
+pub struct Marquee {
+    text: Message,
+    latt: Lattice,
+    cursor: Cursor,
+    sparkle: Sparkle,
+}
+
+impl Marquee {
+    pub const fn new(text: Message, sparkle: Sparkle) -> Self {
+        Marquee {
+            text,
+            latt: [[0; 5]; 5],
+            cursor: Cursor::new(),
+            sparkle,
+        }
+    }
+}
+
+impl Animation for Marquee {
+    /// Columns shown keep scrolling away.
+    fn set_text(&mut self, text: Message) {
+        self.text = text;
+        self.cursor = Cursor::start(&text, self.cursor.back);
+    }
+
+    fn text(&self) -> &Message {
+        &self.text
+    }
+
+    fn set_sparkle(&mut self, sparkle: Sparkle) {
+        self.sparkle = sparkle;
+    }
+
+    fn set_reversed(&mut self, reversed: bool) {
+        if reversed != self.cursor.back {
+            self.cursor = Cursor::start(&self.text, reversed);
+        }
+    }
+
+    fn boundary(&self) -> Option<char> {
+        self.cursor.done
+    }
+
+    fn reset(&mut self) {
+        self.set_text(self.text);
+        self.latt = [[0; 5]; 5];
+    }
+
+    /// Shifts lattice left, or right when reversed, by one column and inserts next one.
+    fn next_frame(&mut self, rng: &mut Rng) -> Lattice {
+        let def = match self.cursor.current(&self.text) {
+            Some(c) => font::col_def(c),
+            None => &ug_max::SPACING,
+        };
+
+        let col = def.get(self.cursor.index(def.len())).copied().unwrap_or(0);
+
+        for (rix, row) in self.latt.iter_mut().enumerate() {
+            let mask = 1 << rix;
+            let px = if col & mask == mask {
+                self.sparkle.brightness(rng)
+            } else {
+                0
+            };
+
+            if self.cursor.back {
+                row.rotate_right(1);
+                row[0] = px;
+            } else {
+                row.rotate_left(1);
+                row[4] = px;
+            }
+        }
+
+        self.cursor.advance(&self.text, def.len());
+        self.latt
+    }
+}
+
+/// Chars rise from bottom row, each centered.
+pub struct Vertical {
+    text: Message,
+    latt: Lattice,
+    cursor: Cursor,
+    sparkle: Sparkle,
+}
+
+impl Vertical {
+    pub const fn new(text: Message, sparkle: Sparkle) -> Self {
+        Vertical {
+            text,
+            latt: [[0; 5]; 5],
+            cursor: Cursor::new(),
+            sparkle,
+        }
+    }
+}
+
+impl Animation for Vertical {
+    /// Rows shown keep scrolling away.
+    fn set_text(&mut self, text: Message) {
+        self.text = text;
+        self.cursor = Cursor::start(&text, self.cursor.back);
+    }
+
+    fn text(&self) -> &Message {
+        &self.text
+    }
+
+    fn set_sparkle(&mut self, sparkle: Sparkle) {
+        self.sparkle = sparkle;
+    }
+
+    fn set_reversed(&mut self, reversed: bool) {
+        if reversed != self.cursor.back {
+            self.cursor = Cursor::start(&self.text, reversed);
+        }
+    }
+
+    fn boundary(&self) -> Option<char> {
+        self.cursor.done
+    }
+
+    fn reset(&mut self) {
+        self.set_text(self.text);
+        self.latt = [[0; 5]; 5];
+    }
+
+    /// Shifts lattice up, or down when reversed, by one row and inserts next one.
+    fn next_frame(&mut self, rng: &mut Rng) -> Lattice {
+        let def: &[u8] = match self.cursor.current(&self.text) {
+            Some(c) => font::col_def(c),
+            None => &[],
+        };
+
+        let off = 5usize.saturating_sub(def.len()) / 2;
+        let mask = 1 << self.cursor.index(5);
+
+        let row = if self.cursor.back {
+            self.latt.rotate_right(1);
+            &mut self.latt[0]
+        } else {
+            self.latt.rotate_left(1);
+            &mut self.latt[4]
+        };
+
+        for (cix, px) in row.iter_mut().enumerate() {
+            let col = cix
+                .checked_sub(off)
+                .and_then(|ix| def.get(ix))
+                .copied()
+                .unwrap_or(0);
+
+            *px = if col & mask == mask {
+                self.sparkle.brightness(rng)
+            } else {
+                0
+            };
+        }
+
+        let steps = if def.is_empty() { 1 } else { 5 };
+        self.cursor.advance(&self.text, steps);
+        self.latt
+    }
+}
@@ -0,0 +1,230 @@
+//! UARTE0 console over micro:bit USB serial bridge, 115200 8N1.
+
+use crate::{gateway, memory::Lattice, message::Message};
+use core::fmt::Write;
+use microbit::{
+    board::UartPins,
+    hal::prelude::*,
+    hal::uarte::{Baudrate, Parity, Uarte, UarteRx, UarteTx},
+    pac::UARTE0,
+};
+
+pub const RING_LEN: usize = 64;
+/// Stands in `Ring` for bytes lost, ASCII cancel.
+pub const GAP: u8 = 0x18;
+const TX_BUF_LEN: usize = 32;
+
+pub type Tx = UarteTx<UARTE0>;
+pub type Rx = UarteRx<UARTE0>;
+
+/// Reception is armed on return, each byte raises ENDRX thus `UARTE0_UART0`.
+pub fn init(uarte: UARTE0, pins: UartPins) -> (Tx, Rx) {
+    let uarte = Uarte::new(uarte, pins.into(), Parity::EXCLUDED, Baudrate::BAUD115200);
+
+    let tx_buf = cortex_m::singleton!(: [u8; TX_BUF_LEN] = [0; TX_BUF_LEN]).unwrap();
+    let rx_buf = cortex_m::singleton!(: [u8; 1] = [0; 1]).unwrap();
+    let (tx, mut rx) = uarte.split(tx_buf, rx_buf).unwrap();
+
+    unsafe {
+        (*UARTE0::ptr()).intenset.write(|w| w.endrx().set());
+    }
+    _ = rx.read();
+
+    (tx, rx)
+}
+
+/// Takes finished byte and rearms reception.
+pub fn receive(rx: &mut Rx) -> Option<u8> {
+    let b = rx.read().ok();
+    _ = rx.read();
+    b
+}
+
+pub fn write(tx: &mut Tx, bytes: &[u8]) {
+    _ = tx.bwrite_all(bytes);
+    _ = tx.bflush();
+}
+
+pub fn publish(tx: &mut Tx, topic: &str, payload: &str) {
+    for part in [b"/pub ", topic.as_bytes(), b" ", payload.as_bytes(), b"\r\n"] {
+        _ = tx.bwrite_all(part);
+    }
+    _ = tx.bflush();
+}
+
+/// Mirrored frame, `#` and 25 brightness digits row by row.
+pub fn key_frame(tx: &mut Tx, latt: &Lattice) {
+    let mut line = [0; 28];
+    line[0] = b'#';
+    for (d, px) in line[1..].iter_mut().zip(latt.iter().flatten()) {
+        *d = b'0' + px;
+    }
+    line[26..].copy_from_slice(b"\r\n");
+    write(tx, &line);
+}
+
+/// Mirrored frame changes, `~` and for each pixel changed its letter `a` to `y` and digit.
+pub fn delta_frame(tx: &mut Tx, prev: &Lattice, latt: &Lattice) {
+    let mut line = [0; 53];
+    line[0] = b'~';
+
+    let mut len = 1;
+    let pixels = prev.iter().flatten().zip(latt.iter().flatten());
+    for (ix, (old, new)) in pixels.enumerate() {
+        if old != new {
+            line[len] = b'a' + ix as u8;
+            line[len + 1] = b'0' + new;
+            len += 2;
+        }
+    }
+
+    line[len..len + 2].copy_from_slice(b"\r\n");
+    write(tx, &line[..len + 2]);
+}
+
+/// Text shown, for screen readers.
+pub fn announce(tx: &mut Tx, text: &Message) {
+    for part in [b"> ", text.as_str().as_bytes(), b"\r\n"] {
+        _ = tx.bwrite_all(part);
+    }
+    _ = tx.bflush();
+}
+
+/// Home Assistant MQTT discovery config for each of `gateway::ENTITIES`.
+pub fn discovery(tx: &mut Tx, node: &str) {
+    for (component, object, config) in gateway::ENTITIES {
+        _ = write!(
+            tx,
+            "/pub homeassistant/{component}/{node}/{object}/config {{\"~\":\"mcu/{node}\",{config},"
+        );
+
+        if component != "device_automation" {
+            _ = write!(tx, "\"uniq_id\":\"{node}_{object}\",");
+        }
+
+        _ = write!(
+            tx,
+            "\"dev\":{{\"ids\":\"{node}\",\"name\":\"mcu chats with you\",\"mf\":\"software9119.technology\",\"mdl\":\"micro:bit v2\"}}}}\r\n"
+        );
+    }
+    _ = tx.bflush();
+}
+
+/// Terminals do not echo locally.
+pub fn echo(tx: &mut Tx, b: u8) {
+    match b {
+        b'\r' | b'\n' => write(tx, b"\r\n"),
+        0x08 | 0x7f => write(tx, b"\x08 \x08"),
+        0x00..=0x1f => {}
+        _ => write(tx, &[b]),
+    }
+}
+
+/// Newest byte gets dropped on overflow, `GAP` takes its place once there is room.
+pub struct Ring {
+    buf: [u8; RING_LEN],
+    head: usize,
+    len: usize,
+    dropped: u32,
+    gap: bool,
+}
+
+impl Ring {
+    pub const fn new() -> Self {
+        Ring {
+            buf: [0; RING_LEN],
+            head: 0,
+            len: 0,
+            dropped: 0,
+            gap: false,
+        }
+    }
+
+    /// Bytes lost to overflow.
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push(&mut self, b: u8) -> bool {
+        if self.gap && self.put(GAP) {
+            self.gap = false;
+        }
+
+        if self.gap || !self.put(b) {
+            self.dropped = self.dropped.wrapping_add(1);
+            self.gap = true;
+            return false;
+        }
+        true
+    }
+
+    fn put(&mut self, b: u8) -> bool {
+        if self.len == RING_LEN {
+            return false;
+        }
+
+        self.buf[(self.head + self.len) % RING_LEN] = b;
+        self.len += 1;
+        true
+    }
+
+    pub fn pop(&mut self) -> Option<u8> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let b = self.buf[self.head];
+        self.head = (self.head + 1) % RING_LEN;
+        self.len -= 1;
+
+        Some(b)
+    }
+}
+
+/// Assembles console input into messages.
+pub struct Line {
+    msg: Message,
+    /// `GAP` seen, rest of line is dropped too.
+    broken: bool,
+}
+
+impl Line {
+    pub const fn new() -> Self {
+        Line {
+            msg: Message::EMPTY,
+            broken: false,
+        }
+    }
+
+    /// Yields non-empty line on `\r` or `\n`, none for line with `GAP` in it.
+    pub fn feed(&mut self, b: u8) -> Option<Message> {
+        match b {
+            GAP => {
+                self.msg.clear();
+                self.broken = true;
+                None
+            }
+            b'\r' | b'\n' => {
+                let mut msg = self.msg;
+                self.msg.clear();
+
+                msg.validate();
+                let broken = core::mem::take(&mut self.broken);
+                (!msg.is_empty() && !broken).then_some(msg)
+            }
+            0x08 | 0x7f => {
+                self.msg.pop();
+                None
+            }
+            0x00..=0x1f => None,
+            _ => {
+                _ = self.msg.push(b);
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+//! Soak test driving random stimulus while lockup watchdog and drop counters watch.
+
+use crate::{message::Message, tick};
+
+/// RTC0 ticks between actions, at most.
+const MAX_GAP_TICKS: u32 = 2 * tick::PER_SEC;
+
+/// Synthetic messages, short, long and beyond ASCII.
+pub const MESSAGES: [Message; 3] = [
+    Message::new("soak"),
+    Message::new("The quick brown fox jumps over the lazy dog 0123456789"),
+    Message::new("Žluťoučký kůň ½°"),
+];
+
+#[derive(Clone, Copy)]
+pub enum Action {
+    Effect,
+    Speed,
+    Pause,
+    /// Index into `MESSAGES`.
+    Message(usize),
+    NextMessage,
+    Reverse,
+    Canvas,
+    PressA,
+    PressB,
+    LongA,
+}
+
+/// Actions drawn from, one per draw.
+const ACTIONS: [Action; 10] = [
+    Action::Effect,
+    Action::Speed,
+    Action::Pause,
+    Action::Message(0),
+    Action::NextMessage,
+    Action::Reverse,
+    Action::Canvas,
+    Action::PressA,
+    Action::PressB,
+    Action::LongA,
+];
+
+pub struct Soak {
+    on: bool,
+    /// Xorshift state, never 0.
+    state: u32,
+    since: u32,
+    last: u32,
+    gap: u32,
+    actions: u32,
+    /// Lockup count at start.
+    lockups: u32,
+}
+
+impl Soak {
+    pub const fn new() -> Self {
+        Soak {
+            on: false,
+            state: 1,
+            since: 0,
+            last: 0,
+            gap: 0,
+            actions: 0,
+            lockups: 0,
+        }
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.on
+    }
+
+    pub fn start(&mut self, seed: u32, lockups: u32, now: u32) {
+        *self = Soak {
+            on: true,
+            state: seed | 1,
+            since: now,
+            last: now,
+            lockups,
+            ..Soak::new()
+        };
+    }
+
+    /// Seconds run, actions taken and lockups since start.
+    pub fn stop(&mut self, lockups: u32, now: u32) -> (u32, u32, u32) {
+        self.on = false;
+        let secs = tick::elapsed(self.since, now) / tick::PER_SEC;
+        (secs, self.actions, lockups.wrapping_sub(self.lockups))
+    }
+
+    /// Action due at `now`, if any.
+    pub fn poll(&mut self, now: u32) -> Option<Action> {
+        if !self.on || tick::elapsed(self.last, now) < self.gap {
+            return None;
+        }
+
+        self.last = now;
+        self.gap = self.random() % MAX_GAP_TICKS;
+        self.actions = self.actions.wrapping_add(1);
+
+        let action = ACTIONS[self.random() as usize % ACTIONS.len()];
+        match action {
+            Action::Message(_) => Some(Action::Message(self.random() as usize % MESSAGES.len())),
+            _ => Some(action),
+        }
+    }
+
+    fn random(&mut self) -> u32 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.state = x;
+        x
+    }
+}
@@ -0,0 +1,122 @@
+//! Radio text filter, per sender rate limit and suppression of repeated text.
+//!
+//! Own fragments carry no sender, all of them share one budget. MakeCode strings carry device
+//! serial, each serial gets its own.
+
+#![deny(clippy::indexing_slicing, clippy::unwrap_used)]
+
+use crate::{
+    message::{self, Message},
+    tick,
+};
+
+/// Messages sender can send back to back.
+const BURST: u8 = 3;
+/// RTC0 ticks earning one message.
+const REFILL_TICKS: u32 = 5 * tick::PER_SEC;
+/// Senders tracked, least recently heard one is forgotten.
+const SENDERS: usize = 8;
+/// Same text again within this many ticks is dropped.
+const REPEAT_TICKS: u32 = 30 * tick::PER_SEC;
+/// Recent texts remembered.
+const RECENT: usize = 4;
+
+#[derive(Clone, Copy)]
+struct Sender {
+    /// `None` for own fragments.
+    id: Option<u32>,
+    tokens: u8,
+    at: u32,
+}
+
+#[derive(Clone, Copy)]
+struct Recent {
+    hash: u32,
+    at: u32,
+}
+
+pub struct Filter {
+    senders: [Option<Sender>; SENDERS],
+    recent: [Option<Recent>; RECENT],
+    /// Slot of `recent` to use next.
+    next: usize,
+}
+
+impl Filter {
+    pub const fn new() -> Self {
+        Filter {
+            senders: [None; SENDERS],
+            recent: [None; RECENT],
+            next: 0,
+        }
+    }
+
+    /// `true` when `msg` from `from` may be shown.
+    pub fn pass(&mut self, msg: &Message, from: Option<u32>, now: u32) -> bool {
+        let hash = message::hash(msg.as_str().as_bytes());
+        let repeat = self
+            .recent
+            .iter()
+            .flatten()
+            .any(|r| r.hash == hash && tick::elapsed(r.at, now) < REPEAT_TICKS);
+        if repeat {
+            return false;
+        }
+
+        if !self.take(from, now) {
+            return false;
+        }
+
+        if let Some(r) = self.recent.get_mut(self.next) {
+            *r = Some(Recent { hash, at: now });
+        }
+        self.next = (self.next + 1) % RECENT;
+        true
+    }
+
+    fn take(&mut self, id: Option<u32>, now: u32) -> bool {
+        let ix = self
+            .senders
+            .iter()
+            .position(|s| s.is_some_and(|s| s.id == id));
+        let ix = ix.or_else(|| self.senders.iter().position(Option::is_none));
+        let ix = ix.unwrap_or_else(|| self.stalest(now));
+
+        let Some(slot) = self.senders.get_mut(ix) else {
+            return false;
+        };
+        if !slot.is_some_and(|s| s.id == id) {
+            *slot = Some(Sender {
+                id,
+                tokens: BURST,
+                at: now,
+            });
+        }
+        let Some(sender) = slot.as_mut() else {
+            return false;
+        };
+
+        let earned = tick::elapsed(sender.at, now) / REFILL_TICKS;
+        if earned > 0 {
+            let earned = earned.min(BURST as u32) as u8;
+            sender.tokens = sender.tokens.saturating_add(earned).min(BURST);
+            sender.at = now;
+        }
+
+        if sender.tokens == 0 {
+            return false;
+        }
+        sender.tokens -= 1;
+        true
+    }
+
+    /// Slot heard from longest ago.
+    fn stalest(&self, now: u32) -> usize {
+        (0..SENDERS)
+            .max_by_key(|ix| {
+                let s = self.senders.get(*ix).copied().flatten();
+                s.map_or(u32::MAX, |s| tick::elapsed(s.at, now))
+            })
+            .unwrap_or(0)
+    }
+}
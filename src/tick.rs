@@ -0,0 +1,11 @@
+//! RTC0 tick arithmetic, 32_768 / (PRESCALER + 1) ≈ 100 Hz.
+
+pub const PRESCALER: u32 = 327;
+pub const PER_SEC: u32 = 100;
+
+const COUNTER_MASK: u32 = 0xFF_FFFF;
+
+/// RTC counter is 24 bit wide.
+pub const fn elapsed(since: u32, now: u32) -> u32 {
+    now.wrapping_sub(since) & COUNTER_MASK
+}
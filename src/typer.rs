@@ -0,0 +1,111 @@
+//! Short message typed char by char, each centered, last one held before display clears.
+
+#![deny(
+    clippy::indexing_slicing,
+    clippy::unwrap_used,
+    clippy::arithmetic_side_effects
+)]
+
+use crate::{
+    animation::{Animation, Sparkle},
+    fader,
+    memory::Lattice,
+    message::Message,
+};
+use microbit::hal::Rng;
+
+/// Frames char stays, blank one between chars included.
+const KEY: u8 = 5;
+/// Frames last char stays longer.
+const HOLD: u8 = 10;
+/// Blank frames before typing starts over.
+const CLEAR: u8 = 8;
+
+pub struct Typer {
+    text: Message,
+    latt: Lattice,
+    /// Byte offset of current char, past text while clearing.
+    char_ix: usize,
+    frame: u8,
+    sparkle: Sparkle,
+    /// Char whose period last frame ended.
+    done: Option<char>,
+}
+
+impl Typer {
+    pub const fn new(text: Message, sparkle: Sparkle) -> Self {
+        Typer {
+            text,
+            latt: [[0; 5]; 5],
+            char_ix: 0,
+            frame: 0,
+            sparkle,
+            done: None,
+        }
+    }
+
+    /// Frames of current char, or of clearing.
+    fn period(&self) -> u8 {
+        let Some(c) = self.text.char_at(self.char_ix) else {
+            return CLEAR;
+        };
+
+        let next = self.char_ix.saturating_add(c.len_utf8());
+        if next >= self.text.as_str().len() {
+            KEY.saturating_add(HOLD)
+        } else {
+            KEY
+        }
+    }
+}
+
+impl Animation for Typer {
+    fn set_text(&mut self, text: Message) {
+        self.text = text;
+        self.char_ix = 0;
+        self.frame = 0;
+    }
+
+    fn text(&self) -> &Message {
+        &self.text
+    }
+
+    fn set_sparkle(&mut self, sparkle: Sparkle) {
+        self.sparkle = sparkle;
+    }
+
+    fn reset(&mut self) {
+        self.set_text(self.text);
+    }
+
+    fn boundary(&self) -> Option<char> {
+        self.done
+    }
+
+    fn next_frame(&mut self, rng: &mut Rng) -> Lattice {
+        let period = self.period();
+
+        if self.frame == 0 {
+            let c = self.text.char_at(self.char_ix);
+            self.latt = fader::centered(c, self.sparkle, rng);
+        } else if self.frame == period.saturating_sub(1) {
+            // same chars typed in row stay apart
+            self.latt = [[0; 5]; 5];
+        }
+
+        self.done = None;
+        self.frame = self.frame.saturating_add(1);
+        if self.frame >= period {
+            self.frame = 0;
+            self.done = self.text.char_at(self.char_ix);
+
+            self.char_ix = match self.done {
+                Some(c) => self.char_ix.saturating_add(c.len_utf8()),
+                // cleared, type again
+                None => 0,
+            };
+        }
+
+        self.latt
+    }
+}
@@ -0,0 +1,195 @@
+//! Logical canvas larger than matrix, shown through 5×5 window that stays put, pans by itself or
+//! follows board tilt.
+
+#![deny(clippy::indexing_slicing, clippy::unwrap_used)]
+
+use crate::memory::Lattice;
+
+/// Canvas side at most.
+pub const MAX: usize = 15;
+/// Matrix side, canvas side at least.
+const SIDE: usize = 5;
+/// Tilt, in mg, moving window to canvas edge.
+const TILT_FULL: i32 = 600;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Pan {
+    /// Window where `at` put it.
+    Fixed,
+    /// Window bounces across canvas one pixel per frame.
+    Auto,
+    /// Window follows accelerometer, like peeking through small window.
+    Tilt,
+}
+
+pub struct Viewport {
+    pixels: [[u8; MAX]; MAX],
+    width: usize,
+    height: usize,
+    x: usize,
+    y: usize,
+    pan: Pan,
+    /// Auto pan moving back, per axis.
+    back: (bool, bool),
+    on: bool,
+}
+
+impl Viewport {
+    pub const fn new() -> Self {
+        Viewport {
+            pixels: [[0; MAX]; MAX],
+            width: MAX,
+            height: SIDE,
+            x: 0,
+            y: 0,
+            pan: Pan::Fixed,
+            back: (false, false),
+            on: false,
+        }
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.on
+    }
+
+    /// Window follows tilt.
+    pub fn is_tilted(&self) -> bool {
+        self.on && self.pan == Pan::Tilt
+    }
+
+    /// `off`, `fixed`, `auto`, `tilt`, `size <w> <h>`, `at <x> <y>`, `px <x> <y> <0-9>`
+    /// or `row <y> <0-9 per pixel>`.
+    /// Returns `false` for anything else or out of canvas.
+    pub fn command(&mut self, args: &str) -> bool {
+        let mut words = args.split(' ').filter(|w| !w.is_empty());
+        let verb = words.next().unwrap_or("");
+        let mut num = || words.next().and_then(|w| w.parse::<usize>().ok());
+
+        match verb {
+            "off" => self.on = false,
+            "fixed" => (self.pan, self.on) = (Pan::Fixed, true),
+            "auto" => (self.pan, self.on) = (Pan::Auto, true),
+            "tilt" => (self.pan, self.on) = (Pan::Tilt, true),
+            "size" => {
+                let (Some(w), Some(h)) = (num(), num()) else {
+                    return false;
+                };
+                return self.resize(w, h);
+            }
+            "at" => {
+                let (Some(x), Some(y)) = (num(), num()) else {
+                    return false;
+                };
+                // canvas is never smaller than window, `x` is user's and could overflow
+                if x > self.width - SIDE || y > self.height - SIDE {
+                    return false;
+                }
+                (self.x, self.y) = (x, y);
+            }
+            "px" => {
+                let (Some(x), Some(y), Some(level)) = (num(), num(), num()) else {
+                    return false;
+                };
+                return self.set(x, y, level);
+            }
+            "row" => {
+                let (Some(y), Some(levels)) = (num(), words.next()) else {
+                    return false;
+                };
+                return self.row(y, levels);
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Clears canvas, window back to top left corner.
+    fn resize(&mut self, width: usize, height: usize) -> bool {
+        if !(SIDE..=MAX).contains(&width) || !(SIDE..=MAX).contains(&height) {
+            return false;
+        }
+
+        *self = Viewport {
+            width,
+            height,
+            pan: self.pan,
+            on: self.on,
+            ..Viewport::new()
+        };
+        true
+    }
+
+    fn set(&mut self, x: usize, y: usize, level: usize) -> bool {
+        if x >= self.width || level > 9 {
+            return false;
+        }
+
+        let px = self.pixels.get_mut(y).and_then(|row| row.get_mut(x));
+        match px.filter(|_| y < self.height) {
+            Some(px) => *px = level as u8,
+            None => return false,
+        }
+        true
+    }
+
+    /// Row `y` from left, one digit per pixel.
+    fn row(&mut self, y: usize, levels: &str) -> bool {
+        let digits = levels.bytes().all(|d| d.is_ascii_digit());
+        if !digits || levels.len() > self.width || y >= self.height {
+            return false;
+        }
+
+        let Some(row) = self.pixels.get_mut(y) else {
+            return false;
+        };
+        for (px, d) in row.iter_mut().zip(levels.bytes()) {
+            *px = d - b'0';
+        }
+        true
+    }
+
+    /// `x` and `y` of accelerometer sample in mg place window, level board shows center.
+    pub fn tilt(&mut self, [x, y, _]: [i16; 3]) {
+        let place = |mg: i16, range: usize| {
+            let mg = i32::from(mg).clamp(-TILT_FULL, TILT_FULL) + TILT_FULL;
+            (mg as usize * range + TILT_FULL as usize) / (2 * TILT_FULL as usize)
+        };
+
+        self.x = place(x, self.width - SIDE);
+        self.y = place(y, self.height - SIDE);
+    }
+
+    /// Window content, auto pan moves it after.
+    pub fn lattice(&mut self) -> Lattice {
+        let mut latt = [[0; SIDE]; SIDE];
+
+        let rows = self.pixels.iter().skip(self.y);
+        for (row, src) in latt.iter_mut().zip(rows) {
+            for (px, s) in row.iter_mut().zip(src.iter().skip(self.x)) {
+                *px = *s;
+            }
+        }
+
+        if self.pan == Pan::Auto {
+            let (x, y) = (self.x, self.y);
+            (self.x, self.back.0) = bounce(x, self.back.0, self.width - SIDE);
+            // one row down after each pass there and back
+            if self.x == 0 && x != 0 {
+                (self.y, self.back.1) = bounce(y, self.back.1, self.height - SIDE);
+            }
+        }
+
+        latt
+    }
+}
+
+/// Next of `pos` in `0..=max` moving back and forth.
+fn bounce(pos: usize, back: bool, max: usize) -> (usize, bool) {
+    match (back, pos) {
+        _ if max == 0 => (0, false),
+        (false, p) if p >= max => (p - 1, true),
+        (false, p) => (p + 1, false),
+        (true, 0) => (1, false),
+        (true, p) => (p - 1, true),
+    }
+}